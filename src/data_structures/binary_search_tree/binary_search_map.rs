@@ -0,0 +1,264 @@
+use super::{
+    entry::{Entry, OccupiedEntry, VacantEntry},
+    tree::{Side, Tree},
+    zipper::Zipper,
+};
+use crate::data_structures::traversal::Range;
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::{Extend, FromIterator},
+};
+
+/// A key-value pair stored in the nodes of a `BinarySearchMap`. Pairs are
+/// ordered by key only, so the map can reuse the set's tree operations.
+#[derive(Debug)]
+pub struct KeyValue<K: Ord, V> {
+    pub key: K,
+    pub value: V,
+}
+
+impl<K: Ord, V> PartialEq for KeyValue<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<K: Ord, V> Eq for KeyValue<K, V> {}
+
+impl<K: Ord, V> PartialOrd for KeyValue<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, V> Ord for KeyValue<K, V> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+impl<K: Ord, V> KeyValue<K, V> {
+    fn as_pair(&self) -> (&K, &V) {
+        (&self.key, &self.value)
+    }
+}
+
+/// An ordered map built on the same tree as `BinarySearchTree`, ordered by
+/// key only. Each key is stored at most once.
+pub struct BinarySearchMap<K: Ord, V> {
+    pub(super) root: Tree<KeyValue<K, V>>,
+    pub size: usize,
+    pub(super) balanced: bool,
+}
+
+impl<K: Ord, V: PartialEq> PartialEq for BinarySearchMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Ord + fmt::Debug, V: fmt::Debug> fmt::Debug for BinarySearchMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord, V> Extend<(K, V)> for BinarySearchMap<K, V> {
+    /// Inserts every key-value pair from the iterator, replacing the values
+    /// of keys already in the map
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchMap;
+    ///
+    /// let mut map: BinarySearchMap<i32, &str> = BinarySearchMap::new();
+    /// map.extend(vec![(2, "b"), (1, "a"), (2, "c")]);
+    /// assert_eq!(map.iter().collect::<Vec<_>>(), [(&1, &"a"), (&2, &"c")]);
+    /// ```
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |(key, value)| {
+            self.insert(key, value);
+        });
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for BinarySearchMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = BinarySearchMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V> Default for BinarySearchMap<K, V> {
+    fn default() -> Self {
+        BinarySearchMap::new()
+    }
+}
+
+impl<K: Ord, V> BinarySearchMap<K, V> {
+    /// Creates an empty map that keeps its tree AVL-balanced, like
+    /// `BinarySearchTree::new`
+    pub fn new() -> Self {
+        BinarySearchMap {
+            root: Tree(None),
+            size: 0,
+            balanced: true,
+        }
+    }
+
+    /// Same as `new`, which keeps the tree AVL-balanced
+    pub fn new_balanced() -> Self {
+        BinarySearchMap::new()
+    }
+
+    /// Creates an empty map whose tree never rotates, like
+    /// `BinarySearchTree::new_unbalanced`
+    pub fn new_unbalanced() -> Self {
+        BinarySearchMap {
+            balanced: false,
+            ..BinarySearchMap::new()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn clear(&mut self) {
+        self.root = Tree(None);
+        self.size = 0;
+    }
+
+    /// Inserts a key-value pair, returning the previous value if the key was
+    /// already present. The stored key is left unchanged in that case.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchMap;
+    ///
+    /// let mut map: BinarySearchMap<&str, i32> = BinarySearchMap::new();
+    /// assert_eq!(map.insert("a", 1), None);
+    /// assert_eq!(map.insert("a", 2), Some(1));
+    /// assert_eq!(map.get(&"a"), Some(&2));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Gets the entry for `key` for in-place lookup, update or insertion
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        let mut zipper = Zipper::new(&mut self.root);
+        loop {
+            let Some(entry) = zipper.value() else {
+                return Entry::Vacant(VacantEntry {
+                    zipper,
+                    size: &mut self.size,
+                    balanced: self.balanced,
+                    key,
+                });
+            };
+            match entry.key.cmp(&key) {
+                Ordering::Greater => zipper.descend(Side::Left),
+                Ordering::Less => zipper.descend(Side::Right),
+                Ordering::Equal => {
+                    return Entry::Occupied(OccupiedEntry {
+                        zipper,
+                        size: &mut self.size,
+                        balanced: self.balanced,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value(&self, key: &K) -> Option<(&K, &V)> {
+        self.root
+            .get_by(|entry| entry.key.cmp(key))
+            .map(KeyValue::as_pair)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.root
+            .get_mut_by(|entry| entry.key.cmp(key))
+            .map(|entry| &mut entry.value)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let res = self
+            .root
+            .remove_by(|entry| entry.key.cmp(key), self.balanced);
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res.map(|entry| entry.value)
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.root.min().map(KeyValue::as_pair)
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.root.max().map(KeyValue::as_pair)
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let res = self.root.extract_min(self.balanced);
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res.map(|entry| (entry.key, entry.value))
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let res = self.root.extract_max(self.balanced);
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res.map(|entry| (entry.key, entry.value))
+    }
+
+    /// Returns the entry with the smallest key greater than `key`
+    pub fn successor(&self, key: &K) -> Option<(&K, &V)> {
+        self.root
+            .successor_by(|entry| entry.key.cmp(key))
+            .map(KeyValue::as_pair)
+    }
+
+    /// Returns the entry with the largest key less than `key`
+    pub fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        self.root
+            .predecessor_by(|entry| entry.key.cmp(key))
+            .map(KeyValue::as_pair)
+    }
+
+    /// Iterates over the entries in key order, from either end
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &V)> + ExactSizeIterator {
+        Range::new(self.root.0.as_deref(), &.., self.size).map(KeyValue::as_pair)
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.iter().map(|(_, value)| value)
+    }
+}
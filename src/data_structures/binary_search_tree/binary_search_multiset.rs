@@ -0,0 +1,266 @@
+use super::{
+    comparator::{Comparator, Natural},
+    tree::Tree,
+};
+use crate::data_structures::traversal::Range;
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::{self, Extend, FromIterator},
+};
+
+/// A value stored in a node of a `BinarySearchMultiset` with the number of
+/// copies of it in the multiset
+#[derive(Clone, Debug)]
+pub(super) struct Counted<T> {
+    pub value: T,
+    pub count: usize,
+}
+
+/// An ordered multiset built on the same tree as `BinarySearchTree`. Equal
+/// values share a single node holding their count, instead of each copy
+/// getting its own node, so paths do not grow with the number of copies.
+pub struct BinarySearchMultiset<T, C = Natural> {
+    pub(super) root: Tree<Counted<T>>,
+    pub(super) size: usize,
+    pub(super) balanced: bool,
+    pub(super) cmp: C,
+}
+
+impl<T, C: Comparator<T>> PartialEq for BinarySearchMultiset<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size
+            && self.counts().len() == other.counts().len()
+            && self
+                .counts()
+                .zip(other.counts())
+                .all(|((a, m), (b, n))| m == n && self.cmp.compare(a, b) == Ordering::Equal)
+    }
+}
+
+impl<T: fmt::Debug, C: Comparator<T>> fmt::Debug for BinarySearchMultiset<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Clone, C: Clone> Clone for BinarySearchMultiset<T, C> {
+    fn clone(&self) -> Self {
+        BinarySearchMultiset {
+            root: self.root.clone(),
+            size: self.size,
+            balanced: self.balanced,
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
+impl<T, C: Comparator<T>> Extend<T> for BinarySearchMultiset<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |value| {
+            self.insert(value);
+        });
+    }
+}
+
+impl<T, C: Comparator<T> + Default> FromIterator<T> for BinarySearchMultiset<T, C> {
+    /// Collects the values of an iterator, counting repeated ones.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchMultiset;
+    ///
+    /// let set: BinarySearchMultiset<char> = "mississippi".chars().collect();
+    /// assert_eq!(set.len(), 11);
+    /// assert_eq!(set.distinct_len(), 4);
+    /// assert_eq!(set.count(&'s'), 4);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = BinarySearchMultiset::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, C: Comparator<T> + Default> Default for BinarySearchMultiset<T, C> {
+    fn default() -> Self {
+        BinarySearchMultiset::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> BinarySearchMultiset<T> {
+    /// Creates an empty multiset that keeps its tree AVL-balanced, like
+    /// `BinarySearchTree::new`
+    pub fn new() -> Self {
+        BinarySearchMultiset::with_comparator(Natural)
+    }
+
+    /// Same as `new`, which keeps the tree AVL-balanced
+    pub fn new_balanced() -> Self {
+        BinarySearchMultiset::new()
+    }
+
+    /// Creates an empty multiset whose tree never rotates, like
+    /// `BinarySearchTree::new_unbalanced`
+    pub fn new_unbalanced() -> Self {
+        BinarySearchMultiset {
+            balanced: false,
+            ..BinarySearchMultiset::new()
+        }
+    }
+}
+
+impl<T, C: Comparator<T>> BinarySearchMultiset<T, C> {
+    /// Creates an empty multiset ordered by `cmp`, like
+    /// `BinarySearchTree::with_comparator`
+    pub fn with_comparator(cmp: C) -> Self {
+        BinarySearchMultiset {
+            root: Tree(None),
+            size: 0,
+            balanced: true,
+            cmp,
+        }
+    }
+
+    fn compare_to<'a>(&'a self, value: &'a T) -> impl Fn(&Counted<T>) -> Ordering + 'a {
+        move |entry| self.cmp.compare(&entry.value, value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Number of values in the multiset, counting every copy
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Number of distinct values in the multiset
+    pub fn distinct_len(&self) -> usize {
+        self.root.size()
+    }
+
+    pub fn clear(&mut self) {
+        self.root = Tree(None);
+        self.size = 0;
+    }
+
+    /// Adds a copy of `value`, returning the number of copies now in the
+    /// multiset. If the value is already present, its count is increased and
+    /// `value` itself is dropped.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchMultiset;
+    ///
+    /// let mut set: BinarySearchMultiset<i32> = BinarySearchMultiset::new();
+    /// assert_eq!(set.insert(3), 1);
+    /// assert_eq!(set.insert(3), 2);
+    /// assert_eq!(set.insert(1), 1);
+    /// assert_eq!(set.iter().collect::<Vec<&i32>>(), [&1, &3, &3]);
+    /// assert_eq!(set.distinct().collect::<Vec<&i32>>(), [&1, &3]);
+    /// ```
+    pub fn insert(&mut self, value: T) -> usize {
+        self.size += 1;
+        match self.root.search_by(self.compare_to(&value)) {
+            Ok(index) => {
+                let entry = self.root.select_mut(index).unwrap();
+                entry.count += 1;
+                entry.count
+            }
+            Err(index) => {
+                let entry = Counted { value, count: 1 };
+                self.root.insert_at(index, entry, self.balanced);
+                1
+            }
+        }
+    }
+
+    /// Number of copies of `value` in the multiset
+    pub fn count(&self, value: &T) -> usize {
+        self.root
+            .get_by(self.compare_to(value))
+            .map_or(0, |entry| entry.count)
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.count(value) > 0
+    }
+
+    /// Removes one copy of `value`, returning `false` if there was none. The
+    /// node holding `value` is removed with its last copy.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchMultiset;
+    ///
+    /// let mut set: BinarySearchMultiset<i32> = vec![2, 2, 5].into_iter().collect();
+    /// assert!(set.remove_one(&2));
+    /// assert_eq!(set.count(&2), 1);
+    /// assert!(set.remove_one(&2));
+    /// assert!(!set.remove_one(&2));
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    pub fn remove_one(&mut self, value: &T) -> bool {
+        let Ok(index) = self.root.search_by(self.compare_to(value)) else {
+            return false;
+        };
+
+        let entry = self.root.select_mut(index).unwrap();
+        if entry.count > 1 {
+            entry.count -= 1;
+        } else {
+            self.root.remove_at(index, self.balanced);
+        }
+        self.size -= 1;
+        true
+    }
+
+    /// Removes every copy of `value`, returning how many there were
+    pub fn remove_all(&mut self, value: &T) -> usize {
+        let compare = |entry: &Counted<T>| self.cmp.compare(&entry.value, value);
+        let removed = self.root.remove_by(compare, self.balanced);
+        let count = removed.map_or(0, |entry| entry.count);
+        self.size -= count;
+        count
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.root.min().map(|entry| &entry.value)
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        self.root.max().map(|entry| &entry.value)
+    }
+
+    /// Smallest value greater than `value`
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        self.root
+            .successor_by(self.compare_to(value))
+            .map(|entry| &entry.value)
+    }
+
+    /// Largest value less than `value`
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        self.root
+            .predecessor_by(self.compare_to(value))
+            .map(|entry| &entry.value)
+    }
+
+    /// Iterates over each distinct value with its number of copies, in sorted
+    /// order
+    pub fn counts(&self) -> impl DoubleEndedIterator<Item = (&T, usize)> + ExactSizeIterator {
+        Range::new_by(self.root.0.as_deref(), &.., self.root.size(), |a, b| {
+            self.cmp.compare(&a.value, &b.value)
+        })
+        .map(|entry| (&entry.value, entry.count))
+    }
+
+    /// Iterates over the distinct values in sorted order, once each
+    pub fn distinct(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.counts().map(|(value, _)| value)
+    }
+
+    /// Iterates over the values in sorted order, yielding every copy
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.counts()
+            .flat_map(|(value, count)| iter::repeat_n(value, count))
+    }
+}
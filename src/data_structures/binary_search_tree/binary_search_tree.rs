@@ -1,36 +1,57 @@
 use super::{
-    traversal::{
-        InorderTraversal, LevelOrderTraversal, PostorderTraversal, PreorderTraversal,
-        ReverseOrderTraversal,
-    },
-    tree::Tree,
+    comparator::{ByKey, Comparator, Natural},
+    cursor::{Cursor, CursorMut},
+    iter::{ExtractIf, IntoIter, Iter, LevelOrder, Postorder, Preorder},
+    node::Node,
+    set_ops::{Difference, Intersection, SymmetricDifference, Union},
+    tree::{self, Tree},
+    validation::ValidationReport,
+};
+use crate::data_structures::traversal::{
+    LevelOrderTraversal, PostorderTraversal, PreorderTraversal, Range,
 };
 use std::{
-    cmp::PartialEq,
-    collections::VecDeque,
+    cmp::{Ordering, PartialEq},
     fmt,
-    iter::{Extend, FromIterator},
+    iter::{Extend, FromIterator, Rev},
+    mem,
+    ops::{Bound, RangeBounds},
 };
 
-#[derive(Debug)]
-pub struct BinarySearchTree<T: Ord> {
-    root: Tree<T>,
+pub struct BinarySearchTree<T, C = Natural> {
+    pub(super) root: Tree<T>,
     pub size: usize,
+    pub(super) balanced: bool,
+    pub(super) cmp: C,
 }
 
-impl<T: Ord> PartialEq for BinarySearchTree<T> {
+impl<T, C: Comparator<T>> PartialEq for BinarySearchTree<T, C> {
+    /// Trees are equal if they hold the same elements in the same order, as
+    /// decided by this tree's comparator
     fn eq(&self, other: &Self) -> bool {
-        self.sorted_vec() == other.sorted_vec()
+        self.size == other.size
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| self.cmp.compare(a, b) == Ordering::Equal)
+    }
+}
+
+impl<T: fmt::Debug, C: Comparator<T>> fmt::Debug for BinarySearchTree<T, C> {
+    /// Lists the elements in sorted order. Walking the nodes iteratively
+    /// keeps formatting a degenerate tree from overflowing the stack.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
     }
 }
 
-impl<T: Ord + fmt::Debug> fmt::Display for BinarySearchTree<T> {
+impl<T: fmt::Debug, C: Comparator<T>> fmt::Display for BinarySearchTree<T, C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self.sorted_vec())
     }
 }
 
-impl<T: Ord> Extend<T> for BinarySearchTree<T> {
+impl<T, C: Comparator<T>> Extend<T> for BinarySearchTree<T, C> {
     /// Extends BinarySearchTree elements from iterators
     /// # Example:
     /// ```
@@ -48,7 +69,7 @@ impl<T: Ord> Extend<T> for BinarySearchTree<T> {
     }
 }
 
-impl<T: Ord> FromIterator<T> for BinarySearchTree<T> {
+impl<T, C: Comparator<T> + Default> FromIterator<T> for BinarySearchTree<T, C> {
     /// Extends BinarySearchTree with elements from the iterator.
     /// # Examples:
     /// ```
@@ -63,29 +84,291 @@ impl<T: Ord> FromIterator<T> for BinarySearchTree<T> {
     /// assert_eq!(tree.sorted_vec(), [&0, &1, &3, &4, &5, &7]);
     /// ```
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut tree = BinarySearchTree::new();
+        let mut tree = BinarySearchTree::default();
         tree.extend(iter);
         tree
     }
 }
 
-impl<T: Ord + Clone> Clone for BinarySearchTree<T> {
+impl<T: Clone, C: Clone> Clone for BinarySearchTree<T, C> {
+    /// Copies the tree with the same shape, in O(n) time
     fn clone(&self) -> Self {
-        let mut new_tree = BinarySearchTree::new();
-
-        for element in self.sorted_vec().iter() {
-            new_tree.insert((*element).clone());
+        BinarySearchTree {
+            root: self.root.clone(),
+            size: self.size,
+            balanced: self.balanced,
+            cmp: self.cmp.clone(),
         }
+    }
+}
+
+impl<'a, T, C: Comparator<T>> IntoIterator for &'a BinarySearchTree<T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T, C> IntoIterator for BinarySearchTree<T, C> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Moves the elements out of the tree in sorted order, from either end.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<String> = vec!["b".to_string(), "a".to_string(), "c".to_string()]
+    ///     .into_iter()
+    ///     .collect();
+    /// let mut iter = tree.into_iter();
+    /// assert_eq!(iter.next_back(), Some("c".to_string()));
+    /// assert_eq!(iter.collect::<Vec<String>>(), ["a", "b"]);
+    /// ```
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter::new(self.root, self.size)
+    }
+}
 
-        new_tree
+impl<T, C: Comparator<T> + Default> Default for BinarySearchTree<T, C> {
+    fn default() -> Self {
+        BinarySearchTree::with_comparator(C::default())
     }
 }
 
 impl<T: Ord> BinarySearchTree<T> {
+    /// Creates an empty tree that keeps itself AVL-balanced, so `insert`,
+    /// `remove`, `extract_min` and `extract_max` rotate as needed to keep
+    /// its height within O(log n) regardless of insertion order. Trees built
+    /// with `Default` and `collect` are balanced the same way.
     pub fn new() -> Self {
+        BinarySearchTree::with_comparator(Natural)
+    }
+
+    /// Creates an empty tree that never rotates, so its shape depends only
+    /// on the order of insertion, and sorted insertions leave a path as deep
+    /// as the tree is large
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    /// tree.extend(1..=7);
+    /// assert_eq!(tree.root(), Some(&1));
+    /// assert_eq!(tree.height(), 7);
+    /// ```
+    pub fn new_unbalanced() -> Self {
+        BinarySearchTree::with_comparator_unbalanced(Natural)
+    }
+
+    /// Same as `new`, which keeps the tree AVL-balanced
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    /// tree.extend(1..=7);
+    /// assert_eq!(tree.root(), Some(&4));
+    /// assert_eq!(tree.preorder().collect::<Vec<&i32>>(), [&4, &2, &1, &3, &6, &5, &7]);
+    /// ```
+    pub fn new_balanced() -> Self {
+        BinarySearchTree::new()
+    }
+
+    /// Builds a perfectly balanced tree from elements in sorted order, in
+    /// O(n) time, instead of inserting them one by one. Returns `None` if the
+    /// elements are not sorted. Like `new`, the tree keeps itself balanced on
+    /// later changes.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree = BinarySearchTree::from_sorted_iter(1..=7).unwrap();
+    /// assert_eq!(tree.preorder().collect::<Vec<&i32>>(), [&4, &2, &1, &3, &6, &5, &7]);
+    /// assert!(BinarySearchTree::from_sorted_iter(vec![2, 1]).is_none());
+    /// ```
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Option<Self> {
+        let root = Tree::from_sorted(iter, &Natural)?;
+        Some(BinarySearchTree {
+            size: root.size(),
+            root,
+            balanced: true,
+            cmp: Natural,
+        })
+    }
+
+    /// Rebuilds a tree from its `preorder()` output, in O(n) time. The shape
+    /// is the one inserting the elements in that order into a tree created
+    /// with `new_unbalanced` would give, which for distinct elements is the
+    /// only tree with that preorder, however it was built. Of equal elements,
+    /// later ones go to the right of earlier ones, as with `insert`, so trees
+    /// where rotations moved an element into the left subtree of an equal one
+    /// are not reproduced, and may be rejected; `from_shape` rebuilds those
+    /// exactly. Returns `None` if no tree placed that way has that preorder.
+    /// The rebuilt tree keeps itself balanced if its shape is, and otherwise
+    /// never rotates, like one from `new_unbalanced`.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![4, 2, 6, 1, 3].into_iter().collect();
+    /// let copy = BinarySearchTree::from_preorder(tree.preorder().copied()).unwrap();
+    /// assert_eq!(copy.level_widths(), [1, 2, 2]);
+    /// assert!(BinarySearchTree::from_preorder(vec![4, 2, 6, 3]).is_none());
+    /// ```
+    pub fn from_preorder<I: IntoIterator<Item = T>>(preorder: I) -> Option<Self> {
+        let values: Vec<T> = preorder.into_iter().collect();
+        let mut children = vec![(None, None); values.len()];
+        // Values whose right subtree has not started yet, largest at the
+        // bottom. Every later value must be at least `lower`, the last value
+        // whose right subtree was entered.
+        let mut open: Vec<usize> = Vec::new();
+        let mut lower = None;
+
+        for index in 0..values.len() {
+            let value = &values[index];
+            if lower.is_some_and(|lower: usize| value < &values[lower]) {
+                return None;
+            }
+
+            let mut parent = None;
+            while let Some(&top) = open.last().filter(|&&top| &values[top] <= value) {
+                parent = open.pop();
+                lower = Some(top);
+            }
+            match (parent, open.last()) {
+                (Some(parent), _) => children[parent].1 = Some(index),
+                (None, Some(&parent)) => children[parent].0 = Some(index),
+                (None, None) => {}
+            }
+            open.push(index);
+        }
+
+        Some(BinarySearchTree::from_links(values, &children))
+    }
+
+    /// Rebuilds a tree from its `preorder()` and `inorder()` output. The
+    /// inorder of a search tree is its elements in sorted order, so it says
+    /// nothing about the shape that the preorder does not: the tree is the
+    /// one `from_preorder` builds, and the inorder is checked against it.
+    /// Returns `None` if the traversals do not belong to the same tree.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![2, 1, 3].into_iter().collect();
+    /// let copy = BinarySearchTree::from_traversals(
+    ///     tree.preorder().copied(),
+    ///     tree.inorder().copied(),
+    /// );
+    /// assert_eq!(copy, Some(tree));
+    /// assert!(BinarySearchTree::from_traversals(vec![2, 1, 3], vec![1, 2, 4]).is_none());
+    /// ```
+    pub fn from_traversals<P, I>(preorder: P, inorder: I) -> Option<Self>
+    where
+        P: IntoIterator<Item = T>,
+        I: IntoIterator<Item = T>,
+    {
+        let tree = BinarySearchTree::from_preorder(preorder)?;
+        let mut inorder = inorder.into_iter();
+        let is_same = tree
+            .inorder()
+            .all(|value| inorder.next().as_ref() == Some(value));
+        (is_same && inorder.next().is_none()).then_some(tree)
+    }
+
+    /// Rebuilds a tree of exactly the shape encoded by `shape()`, holding the
+    /// elements of its `preorder()` output. Returns `None` if `shape` is not
+    /// a valid encoding, does not have as many nodes as there are elements,
+    /// or places them out of order.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![3, 1, 2, 5, 4].into_iter().collect();
+    /// let shape = tree.shape();
+    /// assert_eq!(shape.len(), 2);
+    ///
+    /// let copy = BinarySearchTree::from_shape(&shape, tree.preorder().copied()).unwrap();
+    /// assert_eq!(copy.pretty(), tree.pretty());
+    /// assert!(BinarySearchTree::from_shape(&shape, vec![1, 2, 3, 4, 5]).is_none());
+    /// ```
+    pub fn from_shape<I: IntoIterator<Item = T>>(shape: &[u8], preorder: I) -> Option<Self> {
+        let children = tree::links_from_shape(shape)?;
+        let values: Vec<T> = preorder.into_iter().collect();
+        if values.len() != children.len() {
+            return None;
+        }
+
+        let tree = BinarySearchTree::from_links(values, &children);
+        tree.root.is_sorted_by(&Natural).then_some(tree)
+    }
+
+    fn from_links(values: Vec<T>, children: &[(Option<usize>, Option<usize>)]) -> Self {
+        let root = Tree::from_preorder_links(values, children);
+        // Only a tree that is already balanced can keep itself balanced
+        BinarySearchTree {
+            size: root.size(),
+            balanced: root.is_balanced(),
+            root,
+            cmp: Natural,
+        }
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> BinarySearchTree<T, ByKey<F>> {
+    /// Creates an empty tree ordered by the key `f` extracts from each
+    /// element. The key is recomputed at every comparison, so it should be
+    /// cheap to get.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree = BinarySearchTree::by_key(|word: &&str| word.len());
+    /// tree.extend(vec!["ccc", "a", "bb"]);
+    /// assert_eq!(tree.sorted_vec(), [&"a", &"bb", &"ccc"]);
+    /// assert!(tree.contains(&"zz"));
+    /// ```
+    pub fn by_key(f: F) -> Self {
+        BinarySearchTree::with_comparator(ByKey(f))
+    }
+}
+
+impl<T, C: Comparator<T>> BinarySearchTree<T, C> {
+    /// Creates an empty tree ordered by `cmp` instead of the elements' `Ord`
+    /// implementation. Elements that `cmp` finds equal are treated as
+    /// duplicates.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree = BinarySearchTree::with_comparator(|a: &i32, b: &i32| b.cmp(a));
+    /// tree.extend(vec![2, 3, 1]);
+    /// assert_eq!(tree.sorted_vec(), [&3, &2, &1]);
+    /// assert_eq!(tree.successor(&2), Some(&1));
+    /// ```
+    pub fn with_comparator(cmp: C) -> Self {
         BinarySearchTree {
             root: Tree(None),
             size: 0,
+            balanced: true,
+            cmp,
+        }
+    }
+
+    /// Same as `with_comparator`, which keeps the tree AVL-balanced
+    pub fn with_comparator_balanced(cmp: C) -> Self {
+        BinarySearchTree::with_comparator(cmp)
+    }
+
+    /// Like `with_comparator`, for a tree that never rotates, as
+    /// `new_unbalanced` does
+    pub fn with_comparator_unbalanced(cmp: C) -> Self {
+        BinarySearchTree {
+            balanced: false,
+            ..BinarySearchTree::with_comparator(cmp)
         }
     }
 
@@ -98,28 +381,128 @@ impl<T: Ord> BinarySearchTree<T> {
     }
 
     pub fn clear(&mut self) {
-        *self = BinarySearchTree::new();
+        self.root = Tree(None);
+        self.size = 0;
     }
 
     pub fn root(&self) -> Option<&T> {
-        self.root.0.as_ref().map(|node| &node.value)
+        self.root.0.as_deref().map(|node| &node.value)
+    }
+
+    /// Number of levels of the tree, which is 0 for an empty tree. Reads
+    /// the height stored in the root, so takes constant time.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![2, 1, 3, 4].into_iter().collect();
+    /// assert_eq!(tree.height(), 3);
+    /// assert_eq!(tree.depth_of(&2), Some(0));
+    /// assert_eq!(tree.depth_of(&4), Some(2));
+    /// assert_eq!(tree.depth_of(&5), None);
+    /// ```
+    pub fn height(&self) -> usize {
+        self.root.height()
+    }
+
+    /// Number of edges between the root and an element equal to `target`
+    pub fn depth_of<Q: ?Sized>(&self, target: &Q) -> Option<usize>
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root.depth_by(|value| self.cmp.compare(value, target))
+    }
+
+    /// Number of elements without children
+    pub fn leaf_count(&self) -> usize {
+        self.root.leaf_count()
+    }
+
+    /// Number of elements on each level of the tree, from the root down
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![4, 2, 6, 1, 3, 7].into_iter().collect();
+    /// assert_eq!(tree.level_widths(), [1, 2, 3]);
+    /// assert_eq!(tree.leaf_count(), 3);
+    /// assert!(tree.is_balanced());
+    /// ```
+    pub fn level_widths(&self) -> Vec<usize> {
+        self.root.level_widths()
+    }
+
+    /// Whether the subtrees of every element differ in height by at most
+    /// one. Always true for trees created with `new`.
+    pub fn is_balanced(&self) -> bool {
+        self.root.is_balanced()
+    }
+
+    /// Walks the whole tree checking that its elements are in order and that
+    /// the sizes and heights it keeps match its nodes, which is useful when
+    /// testing code that changes the tree's structure. Balanced trees are
+    /// also checked for nodes breaking the AVL property.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = (0..10).collect();
+    /// assert!(tree.validate().is_valid());
+    ///
+    /// tree.size += 1;
+    /// let report = tree.validate();
+    /// assert!(!report.is_valid());
+    /// assert_eq!((report.node_count, report.recorded_size), (10, 11));
+    /// ```
+    pub fn validate(&self) -> ValidationReport {
+        ValidationReport::new(&self.root, self.size, self.balanced, &self.cmp)
     }
 
     pub fn insert(&mut self, value: T) -> bool {
         self.size += 1;
-        self.root.insert(value, true)
+        self.root.insert(value, true, self.balanced, &self.cmp)
     }
 
     pub fn insert_without_dup(&mut self, value: T) -> bool {
-        let res = self.root.insert(value, false);
+        let res = self.root.insert(value, false, self.balanced, &self.cmp);
         if !res {
             self.size += 1;
         }
         res
     }
 
-    pub fn contains(&self, target: &T) -> bool {
-        self.root.contains(target)
+    /// Returns `true` if the tree holds an element equal to `target`, which
+    /// can be of any type the comparator orders the elements against.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<String> = vec!["b".to_string(), "a".to_string()].into_iter().collect();
+    /// assert!(tree.contains("a"));
+    /// assert_eq!(tree.successor("a"), Some(&"b".to_string()));
+    /// ```
+    pub fn contains<Q: ?Sized>(&self, target: &Q) -> bool
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root.contains(target, &self.cmp)
+    }
+
+    /// Returns the stored element equal to `target`
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree = BinarySearchTree::by_key(|pair: &(u32, &str)| pair.0);
+    /// tree.insert((1, "one"));
+    /// assert_eq!(tree.get(&(1, "")), Some(&(1, "one")));
+    /// assert_eq!(tree.get(&(2, "")), None);
+    /// ```
+    pub fn get<Q: ?Sized>(&self, target: &Q) -> Option<&T>
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root.get_by(|value| self.cmp.compare(value, target))
     }
 
     pub fn min(&self) -> Option<&T> {
@@ -130,16 +513,148 @@ impl<T: Ord> BinarySearchTree<T> {
         self.root.max()
     }
 
-    pub fn successor(&self, value: &T) -> Option<&T> {
-        self.root.successor(value)
+    pub fn successor<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root.successor(value, &self.cmp)
+    }
+
+    pub fn predecessor<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root.predecessor(value, &self.cmp)
+    }
+
+    /// Returns the smallest element greater than or equal to `value`. Unlike
+    /// `successor`, an element equal to `value` is returned itself.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let slots: BinarySearchTree<u32> = vec![900, 1000, 1130].into_iter().collect();
+    /// assert_eq!(slots.ceiling(&1000), Some(&1000));
+    /// assert_eq!(slots.ceiling(&1001), Some(&1130));
+    /// assert_eq!(slots.ceiling(&1200), None);
+    /// ```
+    pub fn ceiling<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root
+            .ceiling_by(|stored| self.cmp.compare(stored, value))
+    }
+
+    /// Returns the largest element less than or equal to `value`
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let slots: BinarySearchTree<u32> = vec![900, 1000, 1130].into_iter().collect();
+    /// assert_eq!(slots.floor(&1000), Some(&1000));
+    /// assert_eq!(slots.floor(&999), Some(&900));
+    /// assert_eq!(slots.floor(&800), None);
+    /// ```
+    pub fn floor<Q: ?Sized>(&self, value: &Q) -> Option<&T>
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root.floor_by(|stored| self.cmp.compare(stored, value))
     }
 
-    pub fn predecessor(&self, value: &T) -> Option<&T> {
-        self.root.predecessor(value)
+    /// Returns the element closest to `value` according to `distance`, which
+    /// is only called on the floor and the ceiling of `value`. On a tie the
+    /// smaller element is returned.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![10, 20, 40].into_iter().collect();
+    /// let distance = |a: &i32, b: &i32| a.abs_diff(*b);
+    /// assert_eq!(tree.nearest(&28, distance), Some(&20));
+    /// assert_eq!(tree.nearest(&32, distance), Some(&40));
+    /// assert_eq!(tree.nearest(&30, distance), Some(&20));
+    /// assert_eq!(tree.nearest(&-5, distance), Some(&10));
+    /// ```
+    pub fn nearest<Q: ?Sized, D: PartialOrd, F: Fn(&T, &Q) -> D>(
+        &self,
+        value: &Q,
+        distance: F,
+    ) -> Option<&T>
+    where
+        C: Comparator<T, Q>,
+    {
+        match (self.floor(value), self.ceiling(value)) {
+            (Some(floor), Some(ceiling)) => {
+                if distance(ceiling, value) < distance(floor, value) {
+                    Some(ceiling)
+                } else {
+                    Some(floor)
+                }
+            }
+            (floor, ceiling) => floor.or(ceiling),
+        }
+    }
+
+    /// Returns the element at position `index` (counting from zero) in sorted
+    /// order, in O(height) time.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 5, 3].into_iter().collect();
+    /// assert_eq!(tree.select(0), Some(&0));
+    /// assert_eq!(tree.select(3), Some(&4));
+    /// assert_eq!(tree.select(6), None);
+    /// ```
+    pub fn select(&self, index: usize) -> Option<&T> {
+        self.root.select(index)
+    }
+
+    /// Returns the number of elements less than `value`, in O(height) time.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 5, 3].into_iter().collect();
+    /// assert_eq!(tree.rank(&0), 0);
+    /// assert_eq!(tree.rank(&4), 3);
+    /// assert_eq!(tree.rank(&6), 5);
+    /// ```
+    pub fn rank<Q: ?Sized>(&self, value: &Q) -> usize
+    where
+        C: Comparator<T, Q>,
+    {
+        self.root.rank(value, false, &self.cmp)
+    }
+
+    /// Returns the number of elements within `range`, in O(height) time.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 5, 3].into_iter().collect();
+    /// assert_eq!(tree.count_range(1..5), 3);
+    /// assert_eq!(tree.count_range(1..=5), 4);
+    /// assert_eq!(tree.count_range(..), 6);
+    /// ```
+    pub fn count_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let below_start = match range.start_bound() {
+            Bound::Included(start) => self.root.rank(start, false, &self.cmp),
+            Bound::Excluded(start) => self.root.rank(start, true, &self.cmp),
+            Bound::Unbounded => 0,
+        };
+        let below_end = match range.end_bound() {
+            Bound::Included(end) => self.root.rank(end, true, &self.cmp),
+            Bound::Excluded(end) => self.root.rank(end, false, &self.cmp),
+            Bound::Unbounded => self.size,
+        };
+        below_end.saturating_sub(below_start)
     }
 
     pub fn extract_min(&mut self) -> Option<T> {
-        let res = self.root.extract_min();
+        let res = self.root.extract_min(self.balanced);
         if res.is_some() {
             self.size -= 1;
         }
@@ -147,62 +662,487 @@ impl<T: Ord> BinarySearchTree<T> {
     }
 
     pub fn extract_max(&mut self) -> Option<T> {
-        let res = self.root.extract_max();
+        let res = self.root.extract_max(self.balanced);
         if res.is_some() {
             self.size -= 1;
         }
         res
     }
 
-    pub fn remove(&mut self, target: &T) -> bool {
-        let res = self.root.remove(target);
+    pub fn remove<Q: ?Sized>(&mut self, target: &Q) -> bool
+    where
+        C: Comparator<T, Q>,
+    {
+        let res = self.root.remove(target, self.balanced, &self.cmp);
         if res {
             self.size -= 1;
         }
         res
     }
 
-    pub fn sorted_vec(&self) -> Vec<&T> {
-        self.root.sorted_vec()
+    /// Removes and returns the stored element equal to `target`
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<String> = vec!["a".to_string()].into_iter().collect();
+    /// assert_eq!(tree.take("a"), Some("a".to_string()));
+    /// assert_eq!(tree.take("a"), None);
+    /// assert!(tree.is_empty());
+    /// ```
+    pub fn take<Q: ?Sized>(&mut self, target: &Q) -> Option<T>
+    where
+        C: Comparator<T, Q>,
+    {
+        let cmp = &self.cmp;
+        let res = self
+            .root
+            .remove_by(|value| cmp.compare(value, target), self.balanced);
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res
     }
 
-    pub fn into_sorted_vec(self) -> Vec<T> {
-        self.root.into_sorted_vec()
+    /// Puts `value` in place of a stored element equal to it and returns that
+    /// element, or inserts `value` and returns `None` if there is none.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree = BinarySearchTree::by_key(|pair: &(u32, &str)| pair.0);
+    /// assert_eq!(tree.replace((1, "one")), None);
+    /// assert_eq!(tree.replace((1, "uno")), Some((1, "one")));
+    /// assert_eq!(tree.get(&(1, "")), Some(&(1, "uno")));
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn replace(&mut self, value: T) -> Option<T> {
+        match self
+            .root
+            .search_by(|stored| self.cmp.compare(stored, &value))
+        {
+            Ok(index) => {
+                let stored = self.root.select_mut(index).unwrap();
+                Some(mem::replace(stored, value))
+            }
+            Err(index) => {
+                self.root.insert_at(index, value, self.balanced);
+                self.size += 1;
+                None
+            }
+        }
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, in a single
+    /// O(n) pass. The remaining nodes are relinked into a perfectly balanced
+    /// tree.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = (1..=10).collect();
+    /// tree.retain(|x| x % 3 == 0);
+    /// assert_eq!(tree.sorted_vec(), [&3, &6, &9]);
+    /// assert_eq!(tree.len(), 3);
+    /// ```
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        self.extract_if(|x| !f(x)).for_each(drop);
+    }
+
+    /// Removes every element, returning them in sorted order. The tree is
+    /// empty as soon as this is called, even if the iterator is not consumed.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = vec![3, 1, 2].into_iter().collect();
+    /// assert_eq!(tree.drain().collect::<Vec<i32>>(), [1, 2, 3]);
+    /// assert!(tree.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> IntoIter<T> {
+        IntoIter::new(self.root.take(), mem::take(&mut self.size))
+    }
+
+    /// Returns an iterator that removes and yields, in sorted order, the
+    /// elements for which `pred` returns `true`. Elements not yet visited when
+    /// the iterator is dropped are kept, as is the element `pred` was looking
+    /// at if it panics. The whole walk takes O(n) time, and the kept nodes
+    /// are relinked into a perfectly balanced tree.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = (1..=8).collect();
+    /// let evens: Vec<i32> = tree.extract_if(|x| x % 2 == 0).collect();
+    /// assert_eq!(evens, [2, 4, 6, 8]);
+    /// assert_eq!(tree.sorted_vec(), [&1, &3, &5, &7]);
+    /// ```
+    pub fn extract_if<F: FnMut(&T) -> bool>(&mut self, pred: F) -> ExtractIf<'_, T, F, C> {
+        ExtractIf::new(self, pred)
+    }
+
+    /// Reshapes the tree into a perfectly balanced one in O(n) time, using
+    /// the Day-Stout-Warren algorithm. Nodes are relinked in place rather than
+    /// reallocated.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    /// tree.extend(1..=7);
+    /// assert_eq!(tree.root(), Some(&1));
+    /// tree.rebalance();
+    /// assert_eq!(tree.preorder().collect::<Vec<&i32>>(), [&4, &2, &1, &3, &6, &5, &7]);
+    /// ```
+    pub fn rebalance(&mut self) {
+        self.root.rebalance();
+    }
+
+    /// Splits the tree in two at `key`: elements greater than or equal to
+    /// `key` are moved into the returned tree and smaller ones are kept.
+    /// Nodes are relinked rather than reinserted, in O(height) time for
+    /// balanced trees.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = (1..=6).collect();
+    /// let larger = tree.split_off(&4);
+    /// assert_eq!(tree.sorted_vec(), [&1, &2, &3]);
+    /// assert_eq!(larger.sorted_vec(), [&4, &5, &6]);
+    /// ```
+    pub fn split_off(&mut self, key: &T) -> BinarySearchTree<T, C>
+    where
+        C: Clone,
+    {
+        let larger = self.root.split_off(key, self.balanced, &self.cmp);
+        self.size = self.root.size();
+
+        BinarySearchTree {
+            size: larger.size(),
+            root: larger,
+            balanced: self.balanced,
+            cmp: self.cmp.clone(),
+        }
+    }
+
+    /// Moves every element of `other` into this tree, leaving `other` empty.
+    /// When all of the elements of one tree are less than or equal to those
+    /// of the other, the trees are joined by relinking nodes, in O(height)
+    /// time for balanced trees. Otherwise the elements of `other` are
+    /// inserted one by one. If this tree is balanced and `other` is not,
+    /// `other` is rebalanced first, in O(len) time, so the joined tree stays
+    /// balanced.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = (1..=3).collect();
+    /// let mut other: BinarySearchTree<i32> = (4..=6).collect();
+    /// tree.append(&mut other);
+    /// assert_eq!(tree.sorted_vec(), [&1, &2, &3, &4, &5, &6]);
+    /// assert!(other.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut BinarySearchTree<T, C>) {
+        let other_size = other.size;
+        let mut other_root = other.root.take();
+        other.size = 0;
+        if self.balanced && !other.balanced {
+            other_root.rebalance();
+        }
+
+        let self_before_other = match (self.max(), other_root.min()) {
+            (Some(max), Some(min)) => self.cmp.compare(max, min).is_le(),
+            _ => true,
+        };
+        let other_before_self = match (other_root.max(), self.min()) {
+            (Some(max), Some(min)) => self.cmp.compare(max, min).is_le(),
+            _ => true,
+        };
+
+        if self_before_other {
+            self.root = Tree::join(self.root.take(), other_root, self.balanced);
+        } else if other_before_self {
+            self.root = Tree::join(other_root, self.root.take(), self.balanced);
+        } else {
+            self.extend(IntoIter::new(other_root, other_size));
+            return;
+        }
+
+        self.size += other_size;
+    }
+
+    /// Lazily merges both trees in sorted order. Elements present in both are
+    /// yielded once for each time they appear in the tree holding more
+    /// copies of them.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let a: BinarySearchTree<i32> = vec![1, 3, 5, 7].into_iter().collect();
+    /// let b: BinarySearchTree<i32> = vec![3, 4, 5, 6].into_iter().collect();
+    /// assert_eq!(a.union(&b).collect::<Vec<&i32>>(), [&1, &3, &4, &5, &6, &7]);
+    /// assert_eq!(a.intersection(&b).collect::<Vec<&i32>>(), [&3, &5]);
+    /// assert_eq!(a.difference(&b).collect::<Vec<&i32>>(), [&1, &7]);
+    /// assert_eq!(a.symmetric_difference(&b).collect::<Vec<&i32>>(), [&1, &4, &6, &7]);
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a BinarySearchTree<T, C>) -> Union<'a, T, C> {
+        Union {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+            cmp: &self.cmp,
+        }
     }
 
-    pub fn inorder(&self) -> InorderTraversal<T> {
-        InorderTraversal {
-            stack: Vec::new(),
-            current: self.root.0.as_ref(),
+    /// Lazily yields the elements present in both trees, in sorted order
+    pub fn intersection<'a>(&'a self, other: &'a BinarySearchTree<T, C>) -> Intersection<'a, T, C> {
+        Intersection {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+            cmp: &self.cmp,
         }
     }
 
-    pub fn reverse_order(&self) -> ReverseOrderTraversal<T> {
-        ReverseOrderTraversal {
-            stack: Vec::new(),
-            current: self.root.0.as_ref(),
+    /// Lazily yields the elements of this tree that are not in `other`, in
+    /// sorted order
+    pub fn difference<'a>(&'a self, other: &'a BinarySearchTree<T, C>) -> Difference<'a, T, C> {
+        Difference {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+            cmp: &self.cmp,
         }
     }
 
-    pub fn preorder(&self) -> PreorderTraversal<T> {
-        PreorderTraversal {
-            stack: vec![self.root.0.as_ref()],
-            current: self.root.0.as_ref(),
+    /// Lazily yields the elements present in exactly one of the trees, in
+    /// sorted order
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a BinarySearchTree<T, C>,
+    ) -> SymmetricDifference<'a, T, C> {
+        SymmetricDifference {
+            a: self.iter().peekable(),
+            b: other.iter().peekable(),
+            cmp: &self.cmp,
         }
     }
 
-    pub fn postorder(&self) -> PostorderTraversal<T> {
-        PostorderTraversal {
-            stack: Vec::new(),
-            current: self.root.0.as_ref(),
+    /// Returns `true` if every element of this tree is also in `other`
+    pub fn is_subset(&self, other: &BinarySearchTree<T, C>) -> bool {
+        self.size <= other.size && self.difference(other).next().is_none()
+    }
+
+    /// Returns `true` if every element of `other` is also in this tree
+    pub fn is_superset(&self, other: &BinarySearchTree<T, C>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if the trees have no element in common
+    pub fn is_disjoint(&self, other: &BinarySearchTree<T, C>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Returns a cursor on the smallest element, which moves through the tree
+    /// in sorted order without restarting from the root at each step.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![4, 2, 6, 1, 3].into_iter().collect();
+    /// let mut cursor = tree.cursor_front();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current(), Some(&2));
+    /// assert_eq!(cursor.parent(), Some(&4));
+    /// assert_eq!(cursor.left_child(), Some(&1));
+    /// assert_eq!(cursor.right_child(), Some(&3));
+    /// ```
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor::new(&self.root, 0)
+    }
+
+    /// Returns a cursor on the largest element
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor::new(&self.root, self.size.wrapping_sub(1))
+    }
+
+    /// Returns a cursor on the first copy of `value`, or on the ghost position
+    /// if the tree does not contain it
+    pub fn cursor_at(&self, value: &T) -> Cursor<'_, T> {
+        Cursor::new(&self.root, self.position_of(value))
+    }
+
+    /// Returns a cursor on the smallest element that can also edit the tree
+    /// around it. The tree is held apart while the cursor is in use and put
+    /// back together when it is dropped.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let mut tree: BinarySearchTree<i32> = (1..=6).collect();
+    /// let mut cursor = tree.cursor_front_mut();
+    /// while let Some(&value) = cursor.current() {
+    ///     if value % 2 == 0 {
+    ///         cursor.remove_current();
+    ///     } else {
+    ///         cursor.move_next();
+    ///     }
+    /// }
+    /// assert_eq!(cursor.insert_after(0), Ok(()));
+    /// assert_eq!(cursor.insert_after(2), Err(2));
+    /// drop(cursor);
+    /// assert_eq!(tree.sorted_vec(), [&0, &1, &3, &5]);
+    /// ```
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T, C> {
+        CursorMut::new(self, 0)
+    }
+
+    /// Returns a cursor on the largest element that can also edit the tree
+    /// around it
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T, C> {
+        let index = self.size.wrapping_sub(1);
+        CursorMut::new(self, index)
+    }
+
+    /// Returns a cursor on the first copy of `value` that can also edit the
+    /// tree around it, or on the ghost position if the tree does not contain
+    /// `value`
+    pub fn cursor_at_mut(&mut self, value: &T) -> CursorMut<'_, T, C> {
+        let index = self.position_of(value);
+        CursorMut::new(self, index)
+    }
+
+    /// Position of the first copy of `value` in sorted order, or the size of
+    /// the tree if it is not present
+    fn position_of(&self, value: &T) -> usize {
+        let index = self.root.rank(value, false, &self.cmp);
+        match self.root.select(index) {
+            Some(found) if self.cmp.compare(found, value) == Ordering::Equal => index,
+            _ => self.size,
         }
     }
 
-    pub fn level_order(&self) -> LevelOrderTraversal<T> {
-        let mut deque = VecDeque::new();
-        if let Some(root) = self.root.0.as_ref() {
-            deque.push_back(root);
+    pub fn sorted_vec(&self) -> Vec<&T> {
+        self.root.sorted_vec()
+    }
+
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.root.into_sorted_vec()
+    }
+
+    /// Returns a double-ended iterator over the elements within `range` in
+    /// sorted order. Creating it takes O(height) time, after which each
+    /// element is yielded in amortized constant time.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 5, 3].into_iter().collect();
+    /// assert_eq!(tree.range(1..5).collect::<Vec<&i32>>(), [&1, &3, &4]);
+    /// assert_eq!(tree.range(4..).rev().collect::<Vec<&i32>>(), [&7, &5, &4]);
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Range<'_, Node<T>> {
+        let len = self.count_range((range.start_bound(), range.end_bound()));
+        Range::new_by(self.root.0.as_deref(), &range, len, |a, b| {
+            self.cmp.compare(a, b)
+        })
+    }
+
+    /// Returns an iterator over the elements in sorted order, which can also
+    /// be consumed from the back.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 5, 3].into_iter().collect();
+    /// let mut iter = tree.iter();
+    /// assert_eq!(iter.len(), 6);
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next_back(), Some(&7));
+    /// assert_eq!(iter.collect::<Vec<&i32>>(), [&1, &3, &4, &5]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            range: Range::new_by(self.root.0.as_deref(), &.., self.size, |a, b| {
+                self.cmp.compare(a, b)
+            }),
         }
-        LevelOrderTraversal { deque }
+    }
+
+    /// The same iterator as `iter`
+    pub fn inorder(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// `iter` consumed from the back, largest element first
+    pub fn reverse_order(&self) -> Rev<Iter<'_, T>> {
+        self.iter().rev()
+    }
+
+    /// Compact encoding of the shape of the tree, without its elements: two
+    /// bits per element, so a quarter of a byte each. Together with the
+    /// `preorder()` output it is enough for `from_shape` to rebuild the tree
+    /// exactly, even with equal elements or after rotations.
+    pub fn shape(&self) -> Vec<u8> {
+        self.root.shape()
+    }
+
+    pub fn preorder(&self) -> Preorder<'_, T> {
+        Preorder(PreorderTraversal::new(self.root.0.as_deref()))
+    }
+
+    pub fn postorder(&self) -> Postorder<'_, T> {
+        Postorder(PostorderTraversal::new(self.root.0.as_deref()))
+    }
+
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        LevelOrder(LevelOrderTraversal::new(self.root.0.as_deref()))
+    }
+}
+
+impl<T: fmt::Display, C> BinarySearchTree<T, C> {
+    /// Describes the shape of the tree in the Graphviz DOT language, with
+    /// each node labelled by its element's `Display` output. The result can
+    /// be rendered with `dot -Tsvg`.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![2, 1, 3].into_iter().collect();
+    /// assert_eq!(
+    ///     tree.to_dot(),
+    ///     "digraph {
+    ///     node0 [label=\"2\"];
+    ///     node0 -> node1;
+    ///     node0 -> node2;
+    ///     node1 [label=\"1\"];
+    ///     node2 [label=\"3\"];
+    /// }
+    /// "
+    /// );
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.root.to_dot()
+    }
+
+    /// Draws the tree sideways for printing to a terminal: the root is on
+    /// the left, each right subtree is drawn above its parent and each left
+    /// subtree below it.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchTree;
+    ///
+    /// let tree: BinarySearchTree<i32> = vec![4, 2, 6, 1, 3, 7].into_iter().collect();
+    /// let expected = [
+    ///     "        ┌── 7",
+    ///     "    ┌── 6",
+    ///     "4",
+    ///     "    │   ┌── 3",
+    ///     "    └── 2",
+    ///     "        └── 1",
+    /// ];
+    /// assert_eq!(tree.pretty().lines().collect::<Vec<&str>>(), expected);
+    /// ```
+    pub fn pretty(&self) -> String {
+        self.root.pretty()
     }
 }
@@ -0,0 +1,43 @@
+use std::{borrow::Borrow, cmp::Ordering};
+
+/// Decides the order of the elements of a `BinarySearchTree`, so a tree can be
+/// ordered by something other than its elements' `Ord` implementation.
+/// Closures taking two elements and returning an `Ordering` are comparators.
+/// `compare` must define a total order, and must not change while the
+/// comparator is in a tree.
+///
+/// `Q` is the type of the values a tree can be searched with. Comparing an
+/// element with a `Q` must agree with comparing it with the element that `Q`
+/// stands for.
+pub trait Comparator<T: ?Sized, Q: ?Sized = T> {
+    fn compare(&self, a: &T, b: &Q) -> Ordering;
+}
+
+/// Orders elements by their `Ord` implementation. This is the comparator of
+/// trees created with `BinarySearchTree::new`, which can be searched with any
+/// type the elements borrow as, such as `str` for `String` elements.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Natural;
+
+/// Orders elements by the key a function extracts from them, returned by
+/// `BinarySearchTree::by_key`
+#[derive(Clone, Copy, Debug)]
+pub struct ByKey<F>(pub F);
+
+impl<T: Borrow<Q> + ?Sized, Q: Ord + ?Sized> Comparator<T, Q> for Natural {
+    fn compare(&self, a: &T, b: &Q) -> Ordering {
+        a.borrow().cmp(b)
+    }
+}
+
+impl<T: ?Sized, K: Ord, F: Fn(&T) -> K> Comparator<T> for ByKey<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).cmp(&(self.0)(b))
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T, &T) -> Ordering> Comparator<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
@@ -0,0 +1,250 @@
+use super::{
+    comparator::{Comparator, Natural},
+    persistent_binary_search_tree::{Link, PersistentBinarySearchTree, PersistentNode},
+};
+use std::{
+    fmt,
+    ops::{Bound, RangeBounds},
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard},
+};
+
+/// An ordered set that can be shared between threads and changed through
+/// `&self`, such as behind an `Arc`.
+///
+/// It holds the current version of a `PersistentBinarySearchTree`. Readers
+/// only lock it long enough to get that version, so lookups and scans run
+/// alongside each other and alongside writers. Writers do not wait for each
+/// other either: each builds the next version from the one it read, copying
+/// only the path it changes, and swaps it in if the root is still the one it
+/// started from. Otherwise another writer got there first, and it starts
+/// over from that writer's version. Only the swap itself holds the lock.
+pub struct ConcurrentBinarySearchTree<T, C = Natural> {
+    current: RwLock<PersistentBinarySearchTree<T, C>>,
+}
+
+/// Ascending scan over the elements of a `ConcurrentBinarySearchTree` within
+/// a range, returned by `ConcurrentBinarySearchTree::range`. It walks the
+/// version that was current when it was created, so it is not affected by
+/// changes made while it runs and never blocks writers.
+pub struct Scan<T, C = Natural> {
+    stack: Vec<Arc<PersistentNode<T>>>,
+    end: Bound<T>,
+    cmp: C,
+}
+
+impl<T, C: Comparator<T> + Default> Default for ConcurrentBinarySearchTree<T, C> {
+    fn default() -> Self {
+        ConcurrentBinarySearchTree::with_comparator(C::default())
+    }
+}
+
+impl<T: fmt::Debug, C: Comparator<T> + Clone> fmt::Debug for ConcurrentBinarySearchTree<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.current().fmt(f)
+    }
+}
+
+impl<T, C> From<PersistentBinarySearchTree<T, C>> for ConcurrentBinarySearchTree<T, C> {
+    /// Shares `version` between threads, starting from its elements
+    fn from(version: PersistentBinarySearchTree<T, C>) -> Self {
+        ConcurrentBinarySearchTree {
+            current: RwLock::new(version),
+        }
+    }
+}
+
+impl<T: Ord> ConcurrentBinarySearchTree<T> {
+    pub fn new() -> Self {
+        ConcurrentBinarySearchTree::with_comparator(Natural)
+    }
+}
+
+impl<T, C: Comparator<T>> ConcurrentBinarySearchTree<T, C> {
+    /// Creates an empty set ordered by `cmp`, like
+    /// `BinarySearchTree::with_comparator`
+    pub fn with_comparator(cmp: C) -> Self {
+        ConcurrentBinarySearchTree::from(PersistentBinarySearchTree::with_comparator(cmp))
+    }
+
+    // Versions are never changed in place, so a thread that panicked while
+    // holding a lock cannot have left a broken one behind, and the poison
+    // can be ignored
+    fn read(&self) -> RwLockReadGuard<'_, PersistentBinarySearchTree<T, C>> {
+        self.current.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The current version, taken without holding the lock while it is read
+    fn current(&self) -> PersistentBinarySearchTree<T, C>
+    where
+        C: Clone,
+    {
+        self.read().clone()
+    }
+
+    /// Swaps in `next` if `base` is still the current version, returning
+    /// whether it was. Versions with the same root hold the same elements,
+    /// and `base` keeps its root alive, so comparing roots is enough.
+    fn compare_and_swap(
+        &self,
+        base: &PersistentBinarySearchTree<T, C>,
+        next: PersistentBinarySearchTree<T, C>,
+    ) -> bool {
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        if !current.ptr_eq(base) {
+            return false;
+        }
+        *current = next;
+        true
+    }
+
+    pub fn is_empty(&self) -> bool
+    where
+        C: Clone,
+    {
+        self.current().is_empty()
+    }
+
+    pub fn len(&self) -> usize
+    where
+        C: Clone,
+    {
+        self.current().len()
+    }
+
+    pub fn contains<Q: ?Sized>(&self, target: &Q) -> bool
+    where
+        C: Comparator<T, Q> + Clone,
+    {
+        self.current().contains(target)
+    }
+}
+
+impl<T: Clone, C: Comparator<T> + Clone> ConcurrentBinarySearchTree<T, C> {
+    /// The current version of the set, which later changes do not affect
+    pub fn snapshot(&self) -> PersistentBinarySearchTree<T, C> {
+        self.current()
+    }
+
+    /// Adds `value`, returning `false` if an equal element was already
+    /// present.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::ConcurrentBinarySearchTree;
+    /// use std::thread;
+    ///
+    /// let visited = ConcurrentBinarySearchTree::new();
+    /// thread::scope(|scope| {
+    ///     for page in ["/a", "/b", "/a"] {
+    ///         let visited = &visited;
+    ///         scope.spawn(move || visited.insert(page.to_string()));
+    ///     }
+    /// });
+    /// assert_eq!(visited.len(), 2);
+    /// assert!(!visited.insert("/b".to_string()));
+    /// ```
+    pub fn insert(&self, value: T) -> bool {
+        self.update(|current| current.insert(value.clone()))
+    }
+
+    /// Removes the element equal to `target`, returning `false` if there was
+    /// none
+    pub fn remove<Q: ?Sized>(&self, target: &Q) -> bool
+    where
+        C: Comparator<T, Q>,
+    {
+        self.update(|current| current.remove(target))
+    }
+
+    /// Builds the next version from the current one with `change` and swaps
+    /// it in, starting over whenever another writer swapped first. Returns
+    /// `false` without swapping if `change` left the version as it was.
+    fn update<F>(&self, change: F) -> bool
+    where
+        F: Fn(&PersistentBinarySearchTree<T, C>) -> PersistentBinarySearchTree<T, C>,
+    {
+        loop {
+            let current = self.snapshot();
+            let next = change(&current);
+            if next.ptr_eq(&current) {
+                return false;
+            }
+            if self.compare_and_swap(&current, next) {
+                return true;
+            }
+        }
+    }
+
+    /// Scans the elements within `range` in ascending order, yielding copies
+    /// of them. The scan is weakly consistent: it sees the set as it was when
+    /// `range` was called, whatever other threads do in the meantime.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::ConcurrentBinarySearchTree;
+    ///
+    /// let set = ConcurrentBinarySearchTree::new();
+    /// (0..10).for_each(|i| {
+    ///     set.insert(i);
+    /// });
+    /// let mut scan = set.range(3..6);
+    /// set.remove(&4);
+    /// assert_eq!(scan.next(), Some(3));
+    /// assert_eq!(scan.collect::<Vec<i32>>(), [4, 5]);
+    /// assert_eq!(set.range(3..6).collect::<Vec<i32>>(), [3, 5]);
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Scan<T, C> {
+        let snapshot = self.snapshot();
+        let mut scan = Scan {
+            stack: Vec::new(),
+            end: range.end_bound().cloned(),
+            cmp: snapshot.cmp.clone(),
+        };
+
+        // Keep the nodes on the path to the start of the range that are
+        // within it, as an inorder traversal would have them on its stack
+        let mut current = snapshot.root.clone();
+        while let Some(node) = current {
+            let after_start = match range.start_bound() {
+                Bound::Included(start) => scan.cmp.compare(&node.value, start).is_ge(),
+                Bound::Excluded(start) => scan.cmp.compare(&node.value, start).is_gt(),
+                Bound::Unbounded => true,
+            };
+            if after_start {
+                current = node.left.clone();
+                scan.stack.push(node);
+            } else {
+                current = node.right.clone();
+            }
+        }
+
+        scan
+    }
+}
+
+impl<T: Clone, C: Comparator<T>> Scan<T, C> {
+    fn push_left_spine(&mut self, mut link: Link<T>) {
+        while let Some(node) = link {
+            link = node.left.clone();
+            self.stack.push(node);
+        }
+    }
+}
+
+impl<T: Clone, C: Comparator<T>> Iterator for Scan<T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let node = self.stack.pop()?;
+        let before_end = match &self.end {
+            Bound::Included(end) => self.cmp.compare(&node.value, end).is_le(),
+            Bound::Excluded(end) => self.cmp.compare(&node.value, end).is_lt(),
+            Bound::Unbounded => true,
+        };
+        if !before_end {
+            self.stack.clear();
+            return None;
+        }
+
+        self.push_left_spine(node.right.clone());
+        Some(node.value.clone())
+    }
+}
@@ -0,0 +1,455 @@
+use super::{
+    binary_search_tree::BinarySearchTree,
+    comparator::{Comparator, Natural},
+    node::Node,
+    tree::{Side, Tree},
+    zipper::Zipper,
+};
+use std::{cmp::Ordering, ptr};
+
+/// A read-only cursor over a `BinarySearchTree`. It sits on one element, or
+/// on a "ghost" position past both ends of the tree, and keeps the path from
+/// the root so moving to the next or previous element takes amortized O(1)
+/// time.
+pub struct Cursor<'a, T: 'a> {
+    root: &'a Tree<T>,
+    path: Vec<&'a Node<T>>,
+    index: usize,
+}
+
+/// A cursor over a `BinarySearchTree` that can also remove the current
+/// element and insert neighbours next to it. The tree is held apart along
+/// the path from the root to the current node, so reading around it and
+/// moving to the next or previous element take amortized O(1) time, while
+/// each edit takes O(height). The tree is put back together when the cursor
+/// is dropped.
+pub struct CursorMut<'a, T: 'a, C: 'a = Natural> {
+    // Focused on the current node, or on the whole tree on the ghost position
+    zipper: Zipper<'a, T>,
+    size: &'a mut usize,
+    balanced: bool,
+    cmp: &'a C,
+    index: usize,
+    // Cleared by `as_cursor`, which focuses on the whole tree instead, and
+    // set again by the next move or edit
+    zipped: bool,
+}
+
+impl<'a, T: 'a> Cursor<'a, T> {
+    /// Creates a cursor on the element at position `index` in sorted order,
+    /// or on the ghost position if `index` is out of bounds
+    pub(super) fn new(root: &'a Tree<T>, index: usize) -> Self {
+        let path = root.path_to_index(index);
+        let index = if path.is_empty() { root.size() } else { index };
+        Cursor { root, path, index }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.path.last().map(|node| &node.value)
+    }
+
+    /// Position of the current element in sorted order, or `None` on the
+    /// ghost position
+    pub fn index(&self) -> Option<usize> {
+        self.path.last().map(|_| self.index)
+    }
+
+    /// Moves to the next element in sorted order. Moving past the last element
+    /// leads to the ghost position, and moving from there to the first one.
+    pub fn move_next(&mut self) {
+        let Some(node) = self.path.last().copied() else {
+            *self = Cursor::new(self.root, 0);
+            return;
+        };
+        self.index += 1;
+
+        if let Some(mut next) = node.right.0.as_deref() {
+            self.path.push(next);
+            while let Some(left) = next.left.0.as_deref() {
+                self.path.push(left);
+                next = left;
+            }
+            return;
+        }
+
+        // Climb until coming up from a left child
+        while let Some(child) = self.path.pop() {
+            match self.path.last() {
+                Some(parent)
+                    if parent
+                        .left
+                        .0
+                        .as_deref()
+                        .is_some_and(|left| ptr::eq(left, child)) =>
+                {
+                    return;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Moves to the previous element in sorted order. Moving past the first
+    /// element leads to the ghost position, and moving from there to the last
+    /// one.
+    pub fn move_prev(&mut self) {
+        let Some(node) = self.path.last().copied() else {
+            *self = Cursor::new(self.root, self.root.size().wrapping_sub(1));
+            return;
+        };
+
+        if let Some(mut prev) = node.left.0.as_deref() {
+            self.index -= 1;
+            self.path.push(prev);
+            while let Some(right) = prev.right.0.as_deref() {
+                self.path.push(right);
+                prev = right;
+            }
+            return;
+        }
+
+        // Climb until coming up from a right child
+        while let Some(child) = self.path.pop() {
+            match self.path.last() {
+                Some(parent)
+                    if parent
+                        .right
+                        .0
+                        .as_deref()
+                        .is_some_and(|right| ptr::eq(right, child)) =>
+                {
+                    self.index -= 1;
+                    return;
+                }
+                _ => {}
+            }
+        }
+        self.index = self.root.size();
+    }
+
+    /// Returns the next element without moving the cursor, in O(height) time
+    pub fn peek_next(&self) -> Option<&'a T> {
+        match self.index() {
+            Some(index) => self.root.select(index + 1),
+            None => self.root.min(),
+        }
+    }
+
+    /// Returns the previous element without moving the cursor, in O(height)
+    /// time
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        match self.index() {
+            Some(index) => index
+                .checked_sub(1)
+                .and_then(|index| self.root.select(index)),
+            None => self.root.max(),
+        }
+    }
+
+    /// Value of the current node's parent in the tree
+    pub fn parent(&self) -> Option<&'a T> {
+        let parent = self.path.len().checked_sub(2)?;
+        Some(&self.path[parent].value)
+    }
+
+    /// Value of the current node's left child in the tree
+    pub fn left_child(&self) -> Option<&'a T> {
+        let node = self.path.last()?;
+        node.left.0.as_ref().map(|left| &left.value)
+    }
+
+    /// Value of the current node's right child in the tree
+    pub fn right_child(&self) -> Option<&'a T> {
+        let node = self.path.last()?;
+        node.right.0.as_ref().map(|right| &right.value)
+    }
+}
+
+impl<'a, T: 'a, C: Comparator<T>> CursorMut<'a, T, C> {
+    /// Creates a cursor on the element at position `index` in sorted order,
+    /// or on the ghost position if `index` is out of bounds
+    pub(super) fn new(tree: &'a mut BinarySearchTree<T, C>, index: usize) -> Self {
+        let BinarySearchTree {
+            root,
+            size,
+            balanced,
+            cmp,
+        } = tree;
+        let mut cursor = CursorMut {
+            zipper: Zipper::new(root),
+            size,
+            balanced: *balanced,
+            cmp,
+            index: 0,
+            zipped: true,
+        };
+        cursor.seek(index);
+        cursor
+    }
+
+    /// Moves the focus down from the whole tree to the element at position
+    /// `index`, or leaves it on the whole tree for the ghost position
+    fn seek(&mut self, index: usize) {
+        self.zipper.rewind(false);
+        self.zipped = true;
+        self.index = index.min(*self.size);
+        if self.index == *self.size {
+            return;
+        }
+
+        let mut index = self.index;
+        loop {
+            let left = self.zipper.focus().0.as_ref().unwrap().left.size();
+            match index.cmp(&left) {
+                Ordering::Less => self.zipper.descend(Side::Left),
+                Ordering::Equal => return,
+                Ordering::Greater => {
+                    index -= left + 1;
+                    self.zipper.descend(Side::Right);
+                }
+            }
+        }
+    }
+
+    /// Focuses on the current element again after `as_cursor`
+    fn zip(&mut self) {
+        if !self.zipped {
+            self.seek(self.index);
+        }
+    }
+
+    /// The current node, which is the root of the focus
+    fn node(&self) -> Option<&Node<T>> {
+        self.index()?;
+        self.zipper.focus().0.as_deref()
+    }
+
+    /// Returns a read-only cursor on the same element. The tree is put back
+    /// together for it, in O(height) time, and this cursor goes back down to
+    /// its element when it is moved or edited next; until then, its reads
+    /// take O(height) time too.
+    pub fn as_cursor(&mut self) -> Cursor<'_, T> {
+        self.zipper.rewind(false);
+        self.zipped = false;
+        Cursor::new(self.zipper.focus(), self.index)
+    }
+
+    /// A read-only cursor on the same element while the tree is whole
+    fn whole(&self) -> Option<Cursor<'_, T>> {
+        (!self.zipped).then(|| Cursor::new(self.zipper.focus(), self.index))
+    }
+
+    pub fn current(&self) -> Option<&T> {
+        if let Some(cursor) = self.whole() {
+            return cursor.current();
+        }
+        self.node().map(|node| &node.value)
+    }
+
+    /// Position of the current element in sorted order, or `None` on the
+    /// ghost position
+    pub fn index(&self) -> Option<usize> {
+        if self.index < *self.size {
+            Some(self.index)
+        } else {
+            None
+        }
+    }
+
+    /// Moves to the next element in sorted order. Moving past the last element
+    /// leads to the ghost position, and moving from there to the first one.
+    pub fn move_next(&mut self) {
+        self.zip();
+        let Some(has_right) = self.node().map(|node| node.right.0.is_some()) else {
+            self.seek(0);
+            return;
+        };
+        self.index += 1;
+
+        if has_right {
+            self.zipper.descend(Side::Right);
+            while self.zipper.focus().0.as_ref().unwrap().left.0.is_some() {
+                self.zipper.descend(Side::Left);
+            }
+            return;
+        }
+
+        // Climb until coming up from a left child, or out of the tree onto
+        // the ghost position
+        while let Some(side) = self.zipper.ascend() {
+            if matches!(side, Side::Left) {
+                return;
+            }
+        }
+    }
+
+    /// Moves to the previous element in sorted order. Moving past the first
+    /// element leads to the ghost position, and moving from there to the last
+    /// one.
+    pub fn move_prev(&mut self) {
+        self.zip();
+        let Some(has_left) = self.node().map(|node| node.left.0.is_some()) else {
+            self.seek(self.size.wrapping_sub(1));
+            return;
+        };
+
+        if has_left {
+            self.index -= 1;
+            self.zipper.descend(Side::Left);
+            while self.zipper.focus().0.as_ref().unwrap().right.0.is_some() {
+                self.zipper.descend(Side::Right);
+            }
+            return;
+        }
+
+        // Climb until coming up from a right child
+        while let Some(side) = self.zipper.ascend() {
+            if matches!(side, Side::Right) {
+                self.index -= 1;
+                return;
+            }
+        }
+        self.index = *self.size;
+    }
+
+    /// Returns the next element without moving the cursor, in O(height) time
+    /// at worst
+    pub fn peek_next(&self) -> Option<&T> {
+        if let Some(cursor) = self.whole() {
+            return cursor.peek_next();
+        }
+        let Some(node) = self.node() else {
+            return self.zipper.focus().min();
+        };
+
+        match node.right.min() {
+            Some(next) => Some(next),
+            None => self
+                .zipper
+                .path()
+                .0
+                .iter()
+                .rev()
+                .find(|(_, side)| matches!(side, Side::Left))
+                .map(|(parent, _)| &parent.value),
+        }
+    }
+
+    /// Returns the previous element without moving the cursor, in O(height)
+    /// time at worst
+    pub fn peek_prev(&self) -> Option<&T> {
+        if let Some(cursor) = self.whole() {
+            return cursor.peek_prev();
+        }
+        let Some(node) = self.node() else {
+            return self.zipper.focus().max();
+        };
+
+        match node.left.max() {
+            Some(prev) => Some(prev),
+            None => self
+                .zipper
+                .path()
+                .0
+                .iter()
+                .rev()
+                .find(|(_, side)| matches!(side, Side::Right))
+                .map(|(parent, _)| &parent.value),
+        }
+    }
+
+    /// Value of the current node's parent in the tree
+    pub fn parent(&self) -> Option<&T> {
+        if let Some(cursor) = self.whole() {
+            return cursor.parent();
+        }
+        self.node()?;
+        self.zipper.path().0.last().map(|(parent, _)| &parent.value)
+    }
+
+    /// Value of the current node's left child in the tree
+    pub fn left_child(&self) -> Option<&T> {
+        if let Some(cursor) = self.whole() {
+            return cursor.left_child();
+        }
+        self.node()?.left.0.as_ref().map(|left| &left.value)
+    }
+
+    /// Value of the current node's right child in the tree
+    pub fn right_child(&self) -> Option<&T> {
+        if let Some(cursor) = self.whole() {
+            return cursor.right_child();
+        }
+        self.node()?.right.0.as_ref().map(|right| &right.value)
+    }
+
+    /// Whether `a` may come before `b` in the tree
+    fn fits(&self, a: &T, b: &T) -> bool {
+        self.cmp.compare(a, b).is_le()
+    }
+
+    /// Removes and returns the current element, moving the cursor to the
+    /// next one. Does nothing on the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.zip();
+        let position = self.node()?.left.size();
+        let res = self.zipper.focus_mut().remove_at(position, self.balanced);
+        self.zipper.rewind(self.balanced);
+        *self.size -= 1;
+        self.seek(self.index);
+        res
+    }
+
+    /// Inserts `value` just before the current element, or as the last
+    /// element on the ghost position. The cursor stays on the current
+    /// element. Gives `value` back if it would break the ordering of the tree.
+    pub fn insert_before(&mut self, value: T) -> Result<(), T> {
+        let fits_after = self.peek_prev().is_none_or(|prev| self.fits(prev, &value));
+        let fits_before = self
+            .current()
+            .is_none_or(|current| self.fits(&value, current));
+        if !(fits_after && fits_before) {
+            return Err(value);
+        }
+
+        self.zip();
+        // On the ghost position the focus is the whole tree
+        let position = match self.node() {
+            Some(node) => node.left.size(),
+            None => *self.size,
+        };
+        self.zipper
+            .focus_mut()
+            .insert_at(position, value, self.balanced);
+        self.zipper.rewind(self.balanced);
+        *self.size += 1;
+        self.seek(self.index + 1);
+        Ok(())
+    }
+
+    /// Inserts `value` just after the current element, or as the first element
+    /// on the ghost position. The cursor stays on the current element. Gives
+    /// `value` back if it would break the ordering of the tree.
+    pub fn insert_after(&mut self, value: T) -> Result<(), T> {
+        let fits_after = self
+            .current()
+            .is_none_or(|current| self.fits(current, &value));
+        let fits_before = self.peek_next().is_none_or(|next| self.fits(&value, next));
+        if !(fits_after && fits_before) {
+            return Err(value);
+        }
+
+        self.zip();
+        let (position, index) = match self.node() {
+            Some(node) => (node.left.size() + 1, self.index),
+            None => (0, self.index + 1),
+        };
+        self.zipper
+            .focus_mut()
+            .insert_at(position, value, self.balanced);
+        self.zipper.rewind(self.balanced);
+        *self.size += 1;
+        self.seek(index);
+        Ok(())
+    }
+}
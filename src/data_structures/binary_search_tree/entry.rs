@@ -0,0 +1,164 @@
+use super::{binary_search_map::KeyValue, node::Node, zipper::Zipper};
+use std::mem;
+
+/// A view into a single entry of a `BinarySearchMap`, returned by
+/// `BinarySearchMap::entry`. The tree is held apart along the path to the key
+/// for as long as the entry lives, so reading, replacing, inserting or
+/// removing through it does not search the tree again.
+pub enum Entry<'a, K: Ord, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K: Ord, V> {
+    // Focused on the node holding the key
+    pub(super) zipper: Zipper<'a, KeyValue<K, V>>,
+    pub(super) size: &'a mut usize,
+    pub(super) balanced: bool,
+}
+
+pub struct VacantEntry<'a, K: Ord, V> {
+    // Focused on the empty link where the key belongs
+    pub(super) zipper: Zipper<'a, KeyValue<K, V>>,
+    pub(super) size: &'a mut usize,
+    pub(super) balanced: bool,
+    pub(super) key: K,
+}
+
+impl<'a, K: Ord, V> Entry<'a, K, V> {
+    /// Inserts `default` if the entry is vacant, and returns a mutable
+    /// reference to the value in the entry.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchMap;
+    ///
+    /// let mut counts: BinarySearchMap<&str, usize> = BinarySearchMap::new();
+    /// for word in "a b a c a b".split(' ') {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts.iter().collect::<Vec<_>>(), [(&"a", &3), (&"b", &2), (&"c", &1)]);
+    /// ```
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let value = default(&entry.key);
+                entry.insert(value)
+            }
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `f` on the value if the entry is occupied, then returns the entry
+    /// so it can be chained with `or_insert`.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::BinarySearchMap;
+    ///
+    /// let mut map: BinarySearchMap<&str, i32> = BinarySearchMap::new();
+    /// map.entry("a").and_modify(|value| *value += 1).or_insert(10);
+    /// map.entry("a").and_modify(|value| *value += 1).or_insert(10);
+    /// assert_eq!(map.get(&"a"), Some(&11));
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+}
+
+impl<'a, K: Ord, V> OccupiedEntry<'a, K, V> {
+    fn entry(&self) -> &KeyValue<K, V> {
+        self.zipper.value().unwrap()
+    }
+
+    pub fn key(&self) -> &K {
+        &self.entry().key
+    }
+
+    pub fn get(&self) -> &V {
+        &self.entry().value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.zipper.value_mut().unwrap().value
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.zipper.into_focus().0.as_mut().unwrap().value.value
+    }
+
+    /// Replaces the value in the entry, returning the old one
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        let mut zipper = self.zipper;
+        let focus = zipper.focus_mut();
+        let index = focus.0.as_ref().unwrap().left.size();
+        let entry = focus.remove_at(index, self.balanced).unwrap();
+        zipper.finish(self.balanced);
+        *self.size -= 1;
+        (entry.key, entry.value)
+    }
+}
+
+impl<'a, K: Ord, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the key with `value` at the link found when the entry was
+    /// created, and returns a mutable reference to the value
+    pub fn insert(self, value: V) -> &'a mut V {
+        let entry = KeyValue {
+            key: self.key,
+            value,
+        };
+        *self.size += 1;
+        &mut self
+            .zipper
+            .place(Box::new(Node::new(entry)), self.balanced)
+            .value
+    }
+}
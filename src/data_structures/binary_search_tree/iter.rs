@@ -0,0 +1,226 @@
+use super::{binary_search_tree::BinarySearchTree, comparator::Natural, node::Node, tree::Tree};
+use crate::data_structures::traversal::{
+    LevelOrderTraversal, PostorderTraversal, PreorderTraversal, Range,
+};
+use std::collections::VecDeque;
+
+/// Inorder iterator over the elements of a `BinarySearchTree`, which can be
+/// consumed from both ends
+pub struct Iter<'a, T: 'a> {
+    pub(super) range: Range<'a, Node<T>>,
+}
+
+/// Preorder iterator over the elements of a `BinarySearchTree`: each node
+/// before its left and then its right subtree
+pub struct Preorder<'a, T: 'a>(pub(super) PreorderTraversal<'a, Node<T>>);
+
+/// Postorder iterator over the elements of a `BinarySearchTree`: each node
+/// after its left and then its right subtree
+pub struct Postorder<'a, T: 'a>(pub(super) PostorderTraversal<'a, Node<T>>);
+
+/// Iterator over the elements of a `BinarySearchTree` level by level, from
+/// the root down and left to right within a level
+pub struct LevelOrder<'a, T: 'a>(pub(super) LevelOrderTraversal<'a, Node<T>>);
+
+/// Owning inorder iterator over the elements of a `BinarySearchTree`, which
+/// can be consumed from both ends. Nodes are taken apart as the iterator
+/// advances, so no buffer holding every element is allocated.
+pub struct IntoIter<T> {
+    // Both stacks have their top at the back. Nodes on `front` have had their
+    // left subtrees split off onto the stack above them, and nodes on `back`
+    // their right subtrees. Once one stack runs out, the other is split from
+    // its bottom, which holds the elements closest to that end.
+    front: VecDeque<Box<Node<T>>>,
+    back: VecDeque<Box<Node<T>>>,
+    remaining: usize,
+}
+
+/// Iterator returned by `BinarySearchTree::extract_if`, which removes and
+/// yields the elements matching a predicate in sorted order
+pub struct ExtractIf<'a, T, F: FnMut(&T) -> bool, C = Natural> {
+    // The tree is flattened into a vine for the walk. Kept nodes are chained
+    // through their left links, largest first, and the tree is rebuilt from
+    // them when the iterator is dropped.
+    tree: &'a mut BinarySearchTree<T, C>,
+    rest: Tree<T>,
+    kept: Tree<T>,
+    kept_len: usize,
+    pred: F,
+}
+
+impl<'a, T: 'a> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.range.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.range.next_back()
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T: 'a> Iterator for Preorder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+}
+
+impl<'a, T: 'a> Iterator for Postorder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+}
+
+impl<'a, T: 'a> Iterator for LevelOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+}
+
+impl<T> IntoIter<T> {
+    pub(super) fn new(root: Tree<T>, size: usize) -> Self {
+        let mut iter = IntoIter {
+            front: VecDeque::new(),
+            back: VecDeque::new(),
+            remaining: size,
+        };
+        iter.push_left_spine(root);
+        iter
+    }
+
+    fn push_left_spine(&mut self, mut tree: Tree<T>) {
+        while let Some(mut node) = tree.0.take() {
+            tree = node.left.take();
+            self.front.push_back(node);
+        }
+    }
+
+    fn push_right_spine(&mut self, mut tree: Tree<T>) {
+        while let Some(mut node) = tree.0.take() {
+            tree = node.right.take();
+            self.back.push_back(node);
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        if self.front.is_empty() {
+            // The smallest remaining elements are the bottom node of `back`
+            // and its left subtree
+            let mut node = self.back.pop_front()?;
+            let left = node.left.take();
+            self.front.push_back(node);
+            self.push_left_spine(left);
+        }
+
+        let mut node = self.front.pop_back()?;
+        self.push_left_spine(node.right.take());
+        Some(node.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        if self.back.is_empty() {
+            // The largest remaining elements are the bottom node of `front`
+            // and its right subtree
+            let mut node = self.front.pop_front()?;
+            let right = node.right.take();
+            self.back.push_back(node);
+            self.push_right_spine(right);
+        }
+
+        let mut node = self.back.pop_back()?;
+        self.push_right_spine(node.left.take());
+        Some(node.value)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<'a, T, F: FnMut(&T) -> bool, C> ExtractIf<'a, T, F, C> {
+    pub(super) fn new(tree: &'a mut BinarySearchTree<T, C>, pred: F) -> Self {
+        let rest = tree.root.take().into_vine();
+        ExtractIf {
+            tree,
+            rest,
+            kept: Tree(None),
+            kept_len: 0,
+            pred,
+        }
+    }
+
+    fn keep(&mut self, mut node: Box<Node<T>>) {
+        node.left = self.kept.take();
+        self.kept = Tree(Some(node));
+        self.kept_len += 1;
+    }
+}
+
+impl<T, F: FnMut(&T) -> bool, C> Iterator for ExtractIf<'_, T, F, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // The node stays on the vine while `pred` looks at it, so if `pred`
+        // panics the node is still there for `drop` to keep
+        while let Some(node) = self.rest.0.as_ref() {
+            let extract = (self.pred)(&node.value);
+            let mut node = self.rest.0.take().unwrap();
+            self.rest = node.right.take();
+            if extract {
+                self.tree.size -= 1;
+                return Some(node.value);
+            }
+            self.keep(node);
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.tree.size - self.kept_len))
+    }
+}
+
+impl<T, F: FnMut(&T) -> bool, C> Drop for ExtractIf<'_, T, F, C> {
+    /// Keeps the elements that were not visited and rebuilds the tree,
+    /// perfectly balanced, from the kept nodes
+    fn drop(&mut self) {
+        while let Some(mut node) = self.rest.0.take() {
+            self.rest = node.right.take();
+            self.keep(node);
+        }
+        self.tree.root = self.kept.take().into_vine().vine_to_tree(self.kept_len);
+    }
+}
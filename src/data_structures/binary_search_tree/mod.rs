@@ -1,9 +1,47 @@
+#[allow(clippy::module_inception)]
 mod binary_search_tree;
 pub use binary_search_tree::BinarySearchTree;
 
+mod binary_search_map;
+pub use binary_search_map::BinarySearchMap;
+
+mod binary_search_multiset;
+pub use binary_search_multiset::BinarySearchMultiset;
+
+mod comparator;
+pub use comparator::{ByKey, Comparator, Natural};
+
+mod concurrent_binary_search_tree;
+pub use concurrent_binary_search_tree::{ConcurrentBinarySearchTree, Scan};
+
+mod cursor;
+pub use cursor::{Cursor, CursorMut};
+
+mod entry;
+pub use entry::{Entry, OccupiedEntry, VacantEntry};
+
+mod iter;
+pub use iter::{ExtractIf, IntoIter, Iter, LevelOrder, Postorder, Preorder};
+
 mod node;
-mod traversal;
+
+mod persistent_binary_search_tree;
+pub use persistent_binary_search_tree::PersistentBinarySearchTree;
+
+mod render;
+
+#[cfg(feature = "serde")]
+mod serde;
+
+mod set_ops;
+pub use set_ops::{Difference, Intersection, SymmetricDifference, Union};
+
 mod tree;
 
+mod validation;
+pub use validation::ValidationReport;
+
+mod zipper;
+
 #[cfg(test)]
 mod tests;
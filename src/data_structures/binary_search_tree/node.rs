@@ -1,18 +1,39 @@
 use super::tree::Tree;
+use crate::data_structures::traversal::BinaryNode;
 
 #[derive(Debug)]
-pub struct Node<T: Ord> {
+pub struct Node<T> {
     pub value: T,
+    pub height: usize,
+    pub size: usize,
     pub left: Tree<T>,
     pub right: Tree<T>,
 }
 
-impl<T: Ord> Node<T> {
+impl<T> Node<T> {
     pub fn new(value: T) -> Self {
         Node {
             value,
+            height: 1,
+            size: 1,
             left: Tree(None),
             right: Tree(None),
         }
     }
 }
+
+impl<T> BinaryNode for Node<T> {
+    type Value = T;
+
+    fn value(&self) -> &T {
+        &self.value
+    }
+
+    fn left(&self) -> Option<&Self> {
+        self.left.0.as_deref()
+    }
+
+    fn right(&self) -> Option<&Self> {
+        self.right.0.as_deref()
+    }
+}
@@ -0,0 +1,356 @@
+use super::comparator::{Comparator, Natural};
+use crate::data_structures::traversal::{BinaryNode, Range};
+use std::{
+    cmp::{Ordering, max},
+    fmt,
+    iter::FromIterator,
+    sync::Arc,
+};
+
+pub(super) type Link<T> = Option<Arc<PersistentNode<T>>>;
+
+/// A node that may be shared by any number of versions of a tree, so it is
+/// never changed once built
+#[derive(Debug)]
+pub(super) struct PersistentNode<T> {
+    pub value: T,
+    pub height: usize,
+    pub size: usize,
+    pub left: Link<T>,
+    pub right: Link<T>,
+}
+
+impl<T> BinaryNode for PersistentNode<T> {
+    type Value = T;
+
+    fn value(&self) -> &T {
+        &self.value
+    }
+
+    fn left(&self) -> Option<&Self> {
+        self.left.as_deref()
+    }
+
+    fn right(&self) -> Option<&Self> {
+        self.right.as_deref()
+    }
+}
+
+fn height<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+fn make<T>(value: T, left: Link<T>, right: Link<T>) -> Arc<PersistentNode<T>> {
+    Arc::new(PersistentNode {
+        height: 1 + max(height(&left), height(&right)),
+        size: 1 + size(&left) + size(&right),
+        value,
+        left,
+        right,
+    })
+}
+
+/// Builds a perfectly balanced tree from the next `len` values, which must
+/// be sorted
+fn build<T, I: Iterator<Item = T>>(values: &mut I, len: usize) -> Link<T> {
+    if len == 0 {
+        return None;
+    }
+    let left = build(values, len / 2);
+    let value = values.next().unwrap();
+    let right = build(values, len - len / 2 - 1);
+    Some(make(value, left, right))
+}
+
+/// Builds a node from subtrees whose heights differ by at most two,
+/// rotating like `Tree::fix` does. Rotated nodes are copied rather than
+/// changed, since other versions may still point to them.
+fn balance<T: Clone>(value: T, left: Link<T>, right: Link<T>) -> Arc<PersistentNode<T>> {
+    if height(&left) > height(&right) + 1 {
+        let left = left.unwrap();
+        if height(&left.left) >= height(&left.right) {
+            let right = make(value, left.right.clone(), right);
+            make(left.value.clone(), left.left.clone(), Some(right))
+        } else {
+            // Left-right case
+            let pivot = left.right.as_ref().unwrap();
+            let new_left = make(left.value.clone(), left.left.clone(), pivot.left.clone());
+            let new_right = make(value, pivot.right.clone(), right);
+            make(pivot.value.clone(), Some(new_left), Some(new_right))
+        }
+    } else if height(&right) > height(&left) + 1 {
+        let right = right.unwrap();
+        if height(&right.right) >= height(&right.left) {
+            let left = make(value, left, right.left.clone());
+            make(right.value.clone(), Some(left), right.right.clone())
+        } else {
+            // Right-left case
+            let pivot = right.left.as_ref().unwrap();
+            let new_left = make(value, left, pivot.left.clone());
+            let new_right = make(
+                right.value.clone(),
+                pivot.right.clone(),
+                right.right.clone(),
+            );
+            make(pivot.value.clone(), Some(new_left), Some(new_right))
+        }
+    } else {
+        make(value, left, right)
+    }
+}
+
+/// Copies the path down to where `value` belongs. Returns `None` if an equal
+/// value is already present, so the caller can keep the old root.
+fn insert<T: Clone, C: Comparator<T>>(
+    link: &Link<T>,
+    value: T,
+    cmp: &C,
+) -> Option<Arc<PersistentNode<T>>> {
+    let Some(node) = link else {
+        return Some(make(value, None, None));
+    };
+
+    match cmp.compare(&node.value, &value) {
+        Ordering::Greater => {
+            let left = insert(&node.left, value, cmp)?;
+            Some(balance(node.value.clone(), Some(left), node.right.clone()))
+        }
+        Ordering::Less => {
+            let right = insert(&node.right, value, cmp)?;
+            Some(balance(node.value.clone(), node.left.clone(), Some(right)))
+        }
+        Ordering::Equal => None,
+    }
+}
+
+/// Copies the path down to `target` and returns the new subtree without it,
+/// or `None` if it is not present
+fn remove<T: Clone, Q: ?Sized, C: Comparator<T, Q>>(
+    link: &Link<T>,
+    target: &Q,
+    cmp: &C,
+) -> Option<Link<T>> {
+    let node = link.as_ref()?;
+
+    let subtree = match cmp.compare(&node.value, target) {
+        Ordering::Greater => {
+            let left = remove(&node.left, target, cmp)?;
+            balance(node.value.clone(), left, node.right.clone())
+        }
+        Ordering::Less => {
+            let right = remove(&node.right, target, cmp)?;
+            balance(node.value.clone(), node.left.clone(), right)
+        }
+        Ordering::Equal => match (&node.left, &node.right) {
+            (None, child) | (child, None) => return Some(child.clone()),
+            (left, Some(right)) => {
+                let (successor, right) = remove_min(right);
+                balance(successor, left.clone(), right)
+            }
+        },
+    };
+
+    Some(Some(subtree))
+}
+
+fn remove_min<T: Clone>(node: &Arc<PersistentNode<T>>) -> (T, Link<T>) {
+    match &node.left {
+        None => (node.value.clone(), node.right.clone()),
+        Some(left) => {
+            let (min, left) = remove_min(left);
+            let subtree = balance(node.value.clone(), left, node.right.clone());
+            (min, Some(subtree))
+        }
+    }
+}
+
+/// An ordered set whose `insert` and `remove` leave it unchanged and return
+/// a new version instead. Versions share every subtree the change did not
+/// touch, so each change copies only the O(log n) nodes on one path of the
+/// AVL-balanced tree, and cloning a version is O(1). Versions can be sent
+/// to and shared between threads.
+pub struct PersistentBinarySearchTree<T, C = Natural> {
+    pub(super) root: Link<T>,
+    pub(super) cmp: C,
+}
+
+impl<T, C: Comparator<T>> PartialEq for PersistentBinarySearchTree<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| self.cmp.compare(a, b) == Ordering::Equal)
+    }
+}
+
+impl<T: fmt::Debug, C: Comparator<T>> fmt::Debug for PersistentBinarySearchTree<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, C: Clone> Clone for PersistentBinarySearchTree<T, C> {
+    /// Returns the same version, sharing all of its nodes, in O(1) time
+    fn clone(&self) -> Self {
+        PersistentBinarySearchTree {
+            root: self.root.clone(),
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
+impl<T, C: Comparator<T> + Default> FromIterator<T> for PersistentBinarySearchTree<T, C> {
+    /// Sorts the values and builds a balanced tree from them at once, which
+    /// is cheaper than copying a path for each one. Of equal values, the
+    /// first is kept, as `insert` would.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let cmp = C::default();
+        let mut values: Vec<T> = iter.into_iter().collect();
+        values.sort_by(|a, b| cmp.compare(a, b));
+        values.dedup_by(|later, earlier| cmp.compare(earlier, later) == Ordering::Equal);
+
+        let len = values.len();
+        PersistentBinarySearchTree {
+            root: build(&mut values.into_iter(), len),
+            cmp,
+        }
+    }
+}
+
+impl<T, C: Comparator<T> + Default> Default for PersistentBinarySearchTree<T, C> {
+    fn default() -> Self {
+        PersistentBinarySearchTree::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> PersistentBinarySearchTree<T> {
+    pub fn new() -> Self {
+        PersistentBinarySearchTree::with_comparator(Natural)
+    }
+}
+
+impl<T, C: Comparator<T>> PersistentBinarySearchTree<T, C> {
+    /// Creates an empty tree ordered by `cmp`, like
+    /// `BinarySearchTree::with_comparator`
+    pub fn with_comparator(cmp: C) -> Self {
+        PersistentBinarySearchTree { root: None, cmp }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    /// Whether two versions are the same, without comparing their elements:
+    /// a version and its clones share one root until they are changed
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+
+    pub fn contains<Q: ?Sized>(&self, target: &Q) -> bool
+    where
+        C: Comparator<T, Q>,
+    {
+        self.get(target).is_some()
+    }
+
+    /// Returns the stored element equal to `target`
+    pub fn get<Q: ?Sized>(&self, target: &Q) -> Option<&T>
+    where
+        C: Comparator<T, Q>,
+    {
+        let mut current = &self.root;
+
+        while let Some(node) = current {
+            match self.cmp.compare(&node.value, target) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Less => current = &node.right,
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+
+        None
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.iter().next()
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        self.iter().next_back()
+    }
+
+    /// Iterates over the elements in sorted order, from either end
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        Range::new_by(self.root.as_deref(), &.., self.len(), |a, b| {
+            self.cmp.compare(a, b)
+        })
+    }
+}
+
+impl<T: Clone, C: Comparator<T> + Clone> PersistentBinarySearchTree<T, C> {
+    /// Returns a version that also holds `value`, or this version again if
+    /// it already holds an equal element. Only the nodes on the path to
+    /// `value` are copied.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::PersistentBinarySearchTree;
+    ///
+    /// let empty: PersistentBinarySearchTree<i32> = PersistentBinarySearchTree::new();
+    /// let one = empty.insert(1);
+    /// let two = one.insert(2);
+    /// assert_eq!(empty.len(), 0);
+    /// assert_eq!(one.iter().collect::<Vec<&i32>>(), [&1]);
+    /// assert_eq!(two.iter().collect::<Vec<&i32>>(), [&1, &2]);
+    /// assert!(two.insert(2).ptr_eq(&two));
+    /// ```
+    pub fn insert(&self, value: T) -> Self {
+        match insert(&self.root, value, &self.cmp) {
+            Some(root) => PersistentBinarySearchTree {
+                root: Some(root),
+                cmp: self.cmp.clone(),
+            },
+            None => self.clone(),
+        }
+    }
+
+    /// Returns a version without the element equal to `target`, or this
+    /// version again if there is none
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::binary_search_tree::PersistentBinarySearchTree;
+    ///
+    /// // Keeping old versions around is enough for undo
+    /// let mut history: Vec<PersistentBinarySearchTree<&str>> = Vec::new();
+    /// let mut tags: PersistentBinarySearchTree<&str> = vec!["draft", "rust"].into_iter().collect();
+    /// history.push(tags.clone());
+    /// tags = tags.remove(&"draft").insert("published");
+    /// assert_eq!(tags.iter().collect::<Vec<&&str>>(), [&"published", &"rust"]);
+    ///
+    /// tags = history.pop().unwrap();
+    /// assert_eq!(tags.iter().collect::<Vec<&&str>>(), [&"draft", &"rust"]);
+    /// ```
+    pub fn remove<Q: ?Sized>(&self, target: &Q) -> Self
+    where
+        C: Comparator<T, Q>,
+    {
+        match remove(&self.root, target, &self.cmp) {
+            Some(root) => PersistentBinarySearchTree {
+                root,
+                cmp: self.cmp.clone(),
+            },
+            None => self.clone(),
+        }
+    }
+}
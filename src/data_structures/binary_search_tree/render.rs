@@ -0,0 +1,97 @@
+use super::{
+    node::Node,
+    tree::{Side, Tree},
+};
+use std::fmt::{Display, Write};
+
+impl<T: Display> Tree<T> {
+    /// Graphviz description of the tree. Nodes are numbered in preorder,
+    /// which the stored sizes give without a second pass. A node with a
+    /// single child gets an invisible point in place of the missing one, so
+    /// Graphviz still draws the child on its own side.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        let mut stack: Vec<(&Node<T>, usize)> = self
+            .0
+            .as_deref()
+            .map(|node| (node, 0))
+            .into_iter()
+            .collect();
+
+        while let Some((node, id)) = stack.pop() {
+            let label = node
+                .value
+                .to_string()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            writeln!(dot, "    node{} [label=\"{}\"];", id, label).unwrap();
+
+            let left_id = id + 1;
+            let right_id = left_id + node.left.size();
+            let (left, right) = (node.left.0.as_deref(), node.right.0.as_deref());
+            if left.is_none() && right.is_none() {
+                continue;
+            }
+            // Graphviz places siblings in the order their edges appear
+            for (child, child_id) in [(left, left_id), (right, right_id)] {
+                if child.is_some() {
+                    writeln!(dot, "    node{} -> node{};", id, child_id).unwrap();
+                } else {
+                    writeln!(dot, "    null{} [shape=point, style=invis];", id).unwrap();
+                    writeln!(dot, "    node{} -> null{} [style=invis];", id, id).unwrap();
+                }
+            }
+            stack.extend(right.map(|right| (right, right_id)));
+            stack.extend(left.map(|left| (left, left_id)));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Draws the tree on its side, one value per line, with the root on the
+    /// left and larger values above smaller ones
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        // Each node comes with the side of its parent it hangs from, which is
+        // `None` for the root
+        let mut stack: Vec<(&Node<T>, String, Option<Side>, bool)> = self
+            .0
+            .as_deref()
+            .map(|node| (node, String::new(), None, false))
+            .into_iter()
+            .collect();
+
+        while let Some((node, prefix, side, children_pushed)) = stack.pop() {
+            if children_pushed {
+                let branch = match side {
+                    None => "",
+                    Some(Side::Right) => "┌── ",
+                    Some(Side::Left) => "└── ",
+                };
+                writeln!(out, "{}{}{}", prefix, branch, node.value).unwrap();
+                continue;
+            }
+
+            // A vertical bar continues down to a left child only from below
+            // a right child, and up to a right child only from above a left one
+            let extend = |bar_on: Side| {
+                let gap = if side == Some(bar_on) {
+                    "│   "
+                } else {
+                    "    "
+                };
+                format!("{}{}", prefix, gap)
+            };
+            if let Some(left) = node.left.0.as_deref() {
+                stack.push((left, extend(Side::Right), Some(Side::Left), false));
+            }
+            stack.push((node, prefix.clone(), side, true));
+            if let Some(right) = node.right.0.as_deref() {
+                stack.push((right, extend(Side::Left), Some(Side::Right), false));
+            }
+        }
+
+        out
+    }
+}
@@ -0,0 +1,184 @@
+//! `Serialize` and `Deserialize` for the trees of this module, enabled by
+//! the `serde` feature. Sets and multisets are written as a sequence of
+//! their elements in sorted order, and maps as a map with keys in sorted
+//! order. Loading sorts what was read and builds a perfectly balanced tree
+//! from it in O(n) time, so input does not have to come from these types.
+
+use super::{
+    BinarySearchMap, BinarySearchMultiset,
+    binary_search_map::KeyValue,
+    binary_search_multiset::Counted,
+    binary_search_tree::BinarySearchTree,
+    comparator::{Comparator, Natural},
+    concurrent_binary_search_tree::ConcurrentBinarySearchTree,
+    persistent_binary_search_tree::PersistentBinarySearchTree,
+    tree::Tree,
+};
+use serde::{
+    de::{Deserialize, Deserializer, MapAccess, Visitor},
+    ser::{Serialize, SerializeSeq, Serializer},
+};
+use std::{cmp::Ordering, fmt, marker::PhantomData};
+
+impl<T: Serialize, C: Comparator<T>> Serialize for BinarySearchTree<T, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T, C> Deserialize<'de> for BinarySearchTree<T, C>
+where
+    T: Deserialize<'de>,
+    C: Comparator<T> + Default,
+{
+    /// Builds a balanced tree from a sequence in any order, keeping
+    /// duplicates. Like a tree created with `new`, it keeps itself balanced
+    /// on later changes, whichever mode the tree that was written used.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cmp = C::default();
+        let mut values = Vec::<T>::deserialize(deserializer)?;
+        values.sort_by(|a, b| cmp.compare(a, b));
+
+        let root = Tree::from_sorted(values, &cmp).unwrap();
+        Ok(BinarySearchTree {
+            size: root.size(),
+            root,
+            balanced: true,
+            cmp,
+        })
+    }
+}
+
+impl<K: Ord + Serialize, V: Serialize> Serialize for BinarySearchMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K: Ord + Deserialize<'de>, V: Deserialize<'de>> Visitor<'de> for MapVisitor<K, V> {
+    type Value = BinarySearchMap<K, V>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0));
+        while let Some(entry) = access.next_entry::<K, V>()? {
+            entries.push(entry);
+        }
+
+        // The sort is stable, so the last value read for a key is the last
+        // of its run, and wins as it would with `insert`
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut pairs: Vec<KeyValue<K, V>> = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            match pairs.last_mut() {
+                Some(last) if last.key == key => last.value = value,
+                _ => pairs.push(KeyValue { key, value }),
+            }
+        }
+
+        let root = Tree::from_sorted(pairs, &Natural).unwrap();
+        Ok(BinarySearchMap {
+            size: root.size(),
+            root,
+            balanced: true,
+        })
+    }
+}
+
+impl<'de, K: Ord + Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de>
+    for BinarySearchMap<K, V>
+{
+    /// Builds a balanced map, keeping the last value read for a repeated key.
+    /// Like a map created with `new`, it keeps itself balanced on later
+    /// changes.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+impl<T: Serialize, C: Comparator<T>> Serialize for BinarySearchMultiset<T, C> {
+    /// Writes every copy, so the output looks like that of a
+    /// `BinarySearchTree` holding the same values
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self.iter() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T, C> Deserialize<'de> for BinarySearchMultiset<T, C>
+where
+    T: Deserialize<'de>,
+    C: Comparator<T> + Default,
+{
+    /// Builds a balanced multiset, which keeps itself balanced on later
+    /// changes like one created with `new`
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cmp = C::default();
+        let mut values = Vec::<T>::deserialize(deserializer)?;
+        values.sort_by(|a, b| cmp.compare(a, b));
+
+        let size = values.len();
+        let mut entries: Vec<Counted<T>> = Vec::new();
+        for value in values {
+            match entries.last_mut() {
+                Some(last) if cmp.compare(&last.value, &value) == Ordering::Equal => {
+                    last.count += 1
+                }
+                _ => entries.push(Counted { value, count: 1 }),
+            }
+        }
+
+        let by_value = |a: &Counted<T>, b: &Counted<T>| cmp.compare(&a.value, &b.value);
+        Ok(BinarySearchMultiset {
+            root: Tree::from_sorted(entries, &by_value).unwrap(),
+            size,
+            balanced: true,
+            cmp,
+        })
+    }
+}
+
+impl<T: Serialize, C: Comparator<T>> Serialize for PersistentBinarySearchTree<T, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T, C> Deserialize<'de> for PersistentBinarySearchTree<T, C>
+where
+    T: Deserialize<'de>,
+    C: Comparator<T> + Default,
+{
+    /// Builds a balanced tree from a sequence in any order, like
+    /// `FromIterator`
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+impl<T: Serialize + Clone, C: Comparator<T> + Clone> Serialize
+    for ConcurrentBinarySearchTree<T, C>
+{
+    /// Writes the version that is current when serialization starts
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
+impl<'de, T, C> Deserialize<'de> for ConcurrentBinarySearchTree<T, C>
+where
+    T: Deserialize<'de>,
+    C: Comparator<T> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        PersistentBinarySearchTree::deserialize(deserializer).map(ConcurrentBinarySearchTree::from)
+    }
+}
@@ -0,0 +1,142 @@
+use super::{comparator::Comparator, iter::Iter};
+use std::{
+    cmp::{Ordering, max, min},
+    iter::Peekable,
+};
+
+/// Lazy union of two trees, returned by `BinarySearchTree::union`
+pub struct Union<'a, T: 'a, C: 'a> {
+    pub(super) a: Peekable<Iter<'a, T>>,
+    pub(super) b: Peekable<Iter<'a, T>>,
+    pub(super) cmp: &'a C,
+}
+
+/// Lazy intersection of two trees, returned by `BinarySearchTree::intersection`
+pub struct Intersection<'a, T: 'a, C: 'a> {
+    pub(super) a: Peekable<Iter<'a, T>>,
+    pub(super) b: Peekable<Iter<'a, T>>,
+    pub(super) cmp: &'a C,
+}
+
+/// Lazy difference of two trees, returned by `BinarySearchTree::difference`
+pub struct Difference<'a, T: 'a, C: 'a> {
+    pub(super) a: Peekable<Iter<'a, T>>,
+    pub(super) b: Peekable<Iter<'a, T>>,
+    pub(super) cmp: &'a C,
+}
+
+/// Lazy symmetric difference of two trees, returned by
+/// `BinarySearchTree::symmetric_difference`
+pub struct SymmetricDifference<'a, T: 'a, C: 'a> {
+    pub(super) a: Peekable<Iter<'a, T>>,
+    pub(super) b: Peekable<Iter<'a, T>>,
+    pub(super) cmp: &'a C,
+}
+
+/// Compares the next elements of both streams, treating an exhausted stream
+/// as greater than any element so the other one is drained first
+fn compare_next<'a, T, C: Comparator<T>>(
+    a: &mut Peekable<Iter<'a, T>>,
+    b: &mut Peekable<Iter<'a, T>>,
+    cmp: &C,
+) -> Option<Ordering> {
+    match (a.peek(), b.peek()) {
+        (None, None) => None,
+        (Some(_), None) => Some(Ordering::Less),
+        (None, Some(_)) => Some(Ordering::Greater),
+        (Some(x), Some(y)) => Some(cmp.compare(x, y)),
+    }
+}
+
+impl<'a, T: 'a, C: Comparator<T>> Iterator for Union<'a, T, C> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        match compare_next(&mut self.a, &mut self.b, self.cmp)? {
+            Ordering::Less => self.a.next(),
+            Ordering::Greater => self.b.next(),
+            Ordering::Equal => {
+                self.b.next();
+                self.a.next()
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (max(a, b), Some(a + b))
+    }
+}
+
+impl<'a, T: 'a, C: Comparator<T>> Iterator for Intersection<'a, T, C> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let (x, y) = (self.a.peek()?, self.b.peek()?);
+            match self.cmp.compare(x, y) {
+                Ordering::Less => {
+                    self.a.next();
+                }
+                Ordering::Greater => {
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(min(self.a.len(), self.b.len())))
+    }
+}
+
+impl<'a, T: 'a, C: Comparator<T>> Iterator for Difference<'a, T, C> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match compare_next(&mut self.a, &mut self.b, self.cmp)? {
+                Ordering::Less => return self.a.next(),
+                Ordering::Greater => {
+                    // Nothing left in `a`
+                    self.a.peek()?;
+                    self.b.next();
+                }
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a, b) = (self.a.len(), self.b.len());
+        (a.saturating_sub(b), Some(a))
+    }
+}
+
+impl<'a, T: 'a, C: Comparator<T>> Iterator for SymmetricDifference<'a, T, C> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            match compare_next(&mut self.a, &mut self.b, self.cmp)? {
+                Ordering::Less => return self.a.next(),
+                Ordering::Greater => return self.b.next(),
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.a.len() + self.b.len()))
+    }
+}
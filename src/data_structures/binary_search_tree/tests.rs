@@ -1,9 +1,41 @@
-use crate::data_structures::binary_search_tree::BinarySearchTree;
-use std::iter::FromIterator;
+use super::{node::Node, persistent_binary_search_tree::Link, tree::Tree};
+use crate::data_structures::binary_search_tree::{
+    BinarySearchMap, BinarySearchMultiset, BinarySearchTree, ConcurrentBinarySearchTree, Cursor,
+    CursorMut, Entry, Iter, LevelOrder, Natural, PersistentBinarySearchTree, Postorder, Preorder,
+};
+use std::{
+    collections::HashSet,
+    iter::{FromIterator, Rev},
+    ops::Bound,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+/// Checks the stored heights and sizes of every node and that no node's
+/// subtrees differ in height by more than one
+fn assert_avl<T>(tree: &Tree<T>) {
+    let mut stack = vec![tree];
+    while let Some(tree) = stack.pop() {
+        if let Some(node) = tree.0.as_ref() {
+            let (left, right) = (node.left.height(), node.right.height());
+            assert!(left.abs_diff(right) <= 1, "unbalanced node");
+            assert_eq!(node.height, 1 + left.max(right));
+            assert_eq!(node.size, 1 + node.left.size() + node.right.size());
+            stack.push(&node.left);
+            stack.push(&node.right);
+        }
+    }
+}
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn basics() {
-    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new();
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
 
     // Check empty BinarySearchTree behaves correctly
     assert_eq!(tree.is_empty(), true);
@@ -85,6 +117,7 @@ fn basics() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn insert_without_duplication() {
     let mut tree: BinarySearchTree<i32> = BinarySearchTree::new();
     assert_eq!(tree.insert_without_dup(0), false);
@@ -206,7 +239,7 @@ fn extract_max() {
 #[test]
 fn into_sorted_vec() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::new();
-    assert_eq!(tree.into_sorted_vec(), Vec::new());
+    assert_eq!(tree.into_sorted_vec(), Vec::<i32>::new());
 
     let mut tree: BinarySearchTree<i32> = BinarySearchTree::new();
     tree.insert(25);
@@ -250,6 +283,7 @@ fn clone() {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn extend() {
     let mut tree: BinarySearchTree<i32> = BinarySearchTree::new();
     tree.extend(vec![7, 1, 0, 4, 5, 3].into_iter());
@@ -258,6 +292,7 @@ fn extend() {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn from_iter() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::from_iter(Vec::new().into_iter());
     assert!(tree.is_empty());
@@ -273,6 +308,7 @@ fn from_iter() {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn inorder() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::new();
     assert_eq!(tree.inorder().collect::<Vec<&i32>>().len(), 0);
@@ -289,6 +325,7 @@ fn inorder() {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn reverse_order() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::new();
     assert_eq!(tree.reverse_order().collect::<Vec<&i32>>().len(), 0);
@@ -305,6 +342,7 @@ fn reverse_order() {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn preorder() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::new();
     assert_eq!(tree.preorder().collect::<Vec<&i32>>().len(), 0);
@@ -312,7 +350,8 @@ fn preorder() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::from_iter(vec![0].into_iter());
     assert_eq!(tree.preorder().collect::<Vec<&i32>>(), vec![&0]);
 
-    let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 2, 5, 3, 6, 8].into_iter().collect();
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    tree.extend(vec![7, 1, 0, 4, 2, 5, 3, 6, 8]);
     assert_eq!(
         tree.preorder().collect::<Vec<&i32>>(),
         vec![&7, &1, &0, &4, &2, &3, &5, &6, &8]
@@ -321,6 +360,7 @@ fn preorder() {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn postorder() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::new();
     assert_eq!(tree.postorder().collect::<Vec<&i32>>().len(), 0);
@@ -328,7 +368,8 @@ fn postorder() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::from_iter(vec![0].into_iter());
     assert_eq!(tree.postorder().collect::<Vec<&i32>>(), vec![&0]);
 
-    let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 2, 5, 3, 6, 8].into_iter().collect();
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    tree.extend(vec![7, 1, 0, 4, 2, 5, 3, 6, 8]);
     assert_eq!(
         tree.postorder().collect::<Vec<&i32>>(),
         vec![&0, &3, &2, &6, &5, &4, &1, &8, &7]
@@ -337,6 +378,7 @@ fn postorder() {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn level_order() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::new();
     assert_eq!(tree.level_order().collect::<Vec<&i32>>().len(), 0);
@@ -344,7 +386,8 @@ fn level_order() {
     let tree: BinarySearchTree<i32> = BinarySearchTree::from_iter(vec![0].into_iter());
     assert_eq!(tree.level_order().collect::<Vec<&i32>>(), vec![&0]);
 
-    let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 2, 5, 3, 6, 8].into_iter().collect();
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    tree.extend(vec![7, 1, 0, 4, 2, 5, 3, 6, 8]);
     assert_eq!(
         tree.level_order().collect::<Vec<&i32>>(),
         vec![&7, &1, &8, &0, &4, &2, &5, &3, &6]
@@ -390,3 +433,1997 @@ fn display() {
         .collect();
     println!("{}", tree);
 }
+
+#[test]
+fn balanced_insert() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(1..=7);
+    assert_eq!(tree.root(), Some(&4));
+    assert_eq!(
+        tree.preorder().collect::<Vec<&i32>>(),
+        vec![&4, &2, &1, &3, &6, &5, &7]
+    );
+
+    // Sorted bulk loads of 2^k - 1 elements produce a perfect tree
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(1..=1023);
+    assert_eq!(tree.len(), 1023);
+    assert_eq!(
+        tree.level_order().take(3).collect::<Vec<&i32>>(),
+        vec![&512, &256, &768]
+    );
+    assert_eq!(
+        tree.inorder().copied().collect::<Vec<i32>>(),
+        (1..=1023).collect::<Vec<i32>>()
+    );
+    assert_eq!(tree.successor(&511), Some(&512));
+    assert_eq!(tree.predecessor(&1), None);
+
+    // Left-right and right-left rotations
+    let tree: BinarySearchTree<i32> = {
+        let mut tree = BinarySearchTree::new_balanced();
+        tree.extend(vec![3, 1, 2]);
+        tree
+    };
+    assert_eq!(tree.preorder().collect::<Vec<&i32>>(), vec![&2, &1, &3]);
+    let tree: BinarySearchTree<i32> = {
+        let mut tree = BinarySearchTree::new_balanced();
+        tree.extend(vec![1, 3, 2]);
+        tree
+    };
+    assert_eq!(tree.preorder().collect::<Vec<&i32>>(), vec![&2, &1, &3]);
+}
+
+#[test]
+fn balanced_remove() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(1..=7);
+    assert!(tree.remove(&1));
+    assert!(tree.remove(&3));
+    assert!(tree.remove(&2));
+    assert!(!tree.remove(&2));
+    assert_eq!(tree.len(), 4);
+    assert_eq!(tree.preorder().collect::<Vec<&i32>>(), vec![&6, &4, &5, &7]);
+
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(1..=7);
+    assert_eq!(tree.extract_min(), Some(1));
+    assert_eq!(tree.extract_min(), Some(2));
+    assert_eq!(tree.extract_min(), Some(3));
+    assert_eq!(tree.preorder().collect::<Vec<&i32>>(), vec![&6, &4, &5, &7]);
+
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(1..=7);
+    assert_eq!(tree.extract_max(), Some(7));
+    assert_eq!(tree.extract_max(), Some(6));
+    assert_eq!(tree.extract_max(), Some(5));
+    assert_eq!(tree.preorder().collect::<Vec<&i32>>(), vec![&2, &1, &4, &3]);
+}
+
+#[test]
+fn balanced_by_default() {
+    // Sorted loads through every default constructor stay logarithmic
+    const SIZE: i32 = 30_000;
+    let collected: BinarySearchTree<i32> = (0..SIZE).collect();
+    let mut inserted: BinarySearchTree<i32> = BinarySearchTree::new();
+    inserted.extend(0..SIZE);
+    let mut from_sorted = BinarySearchTree::from_sorted_iter(0..SIZE).unwrap();
+    from_sorted.extend(SIZE..2 * SIZE);
+    for tree in [&collected, &inserted, &from_sorted] {
+        assert!(tree.height() <= 22, "{}", tree.height());
+        assert_avl(&tree.root);
+    }
+
+    let map: BinarySearchMap<i32, i32> = (0..SIZE).map(|i| (i, i)).collect();
+    assert_eq!(map.len(), SIZE as usize);
+    assert_avl(&map.root);
+    let mut set: BinarySearchMultiset<i32> = BinarySearchMultiset::new();
+    set.extend(0..SIZE);
+    assert_avl(&set.root);
+
+    // Opting out keeps the shape insertion order gives
+    let mut unbalanced = BinarySearchTree::new_unbalanced();
+    unbalanced.extend(0..100);
+    assert_eq!(unbalanced.height(), 100);
+}
+
+#[test]
+fn balanced_matches_unbalanced() {
+    let mut balanced: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    let mut unbalanced: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+
+    for i in 0..500 {
+        let value = (i * 37) % 101;
+        assert_eq!(balanced.insert(value), unbalanced.insert(value));
+    }
+    for i in 0..300 {
+        let value = (i * 53) % 107;
+        assert_eq!(balanced.remove(&value), unbalanced.remove(&value));
+    }
+    assert_eq!(balanced.extract_min(), unbalanced.extract_min());
+    assert_eq!(balanced.extract_max(), unbalanced.extract_max());
+
+    assert_avl(&balanced.root);
+    assert_eq!(balanced.len(), unbalanced.len());
+    assert!(balanced == unbalanced);
+    assert!(balanced.clone() == unbalanced);
+    assert_eq!(
+        balanced.reverse_order().collect::<Vec<&i32>>(),
+        unbalanced.reverse_order().collect::<Vec<&i32>>()
+    );
+
+    balanced.clear();
+    balanced.extend(1..=3);
+    assert_eq!(balanced.root(), Some(&2));
+}
+
+#[test]
+fn order_statistics() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(tree.select(0), None);
+    assert_eq!(tree.rank(&0), 0);
+    assert_eq!(tree.count_range(..), 0);
+
+    tree.extend(vec![25, 15, 40, 10, 18, 45, 35, 18, 10]);
+    assert_eq!(tree.select(0), Some(&10));
+    assert_eq!(tree.select(1), Some(&10));
+    assert_eq!(tree.select(4), Some(&18));
+    assert_eq!(tree.select(8), Some(&45));
+    assert_eq!(tree.select(9), None);
+
+    assert_eq!(tree.rank(&10), 0);
+    assert_eq!(tree.rank(&15), 2);
+    assert_eq!(tree.rank(&20), 5);
+    assert_eq!(tree.rank(&100), 9);
+
+    assert_eq!(tree.count_range(10..18), 3);
+    assert_eq!(tree.count_range(10..=18), 5);
+    assert_eq!(tree.count_range(..25), 5);
+    assert_eq!(tree.count_range(25..), 4);
+    assert_eq!(
+        tree.count_range((std::ops::Bound::Excluded(10), std::ops::Bound::Unbounded)),
+        7
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = tree.count_range(40..10);
+    assert_eq!(empty, 0);
+
+    // Sizes stay correct through removals
+    assert!(tree.remove(&18));
+    assert_eq!(tree.extract_min(), Some(10));
+    assert_eq!(tree.extract_max(), Some(45));
+    assert_eq!(tree.sorted_vec(), vec![&10, &15, &18, &25, &35, &40]);
+    assert_eq!(tree.select(2), Some(&18));
+    assert_eq!(tree.rank(&25), 3);
+    assert_eq!(tree.count_range(15..=35), 4);
+}
+
+#[test]
+fn order_statistics_match_sorted_vec() {
+    for mut tree in [
+        BinarySearchTree::new_unbalanced(),
+        BinarySearchTree::new_balanced(),
+    ] {
+        for i in 0..300 {
+            tree.insert((i * 37) % 101);
+        }
+        for i in 0..100 {
+            tree.remove(&((i * 53) % 107));
+        }
+
+        let sorted: Vec<i32> = tree.inorder().copied().collect();
+        for (index, value) in sorted.iter().enumerate() {
+            assert_eq!(tree.select(index), Some(value));
+            assert_eq!(tree.rank(value), sorted.partition_point(|x| x < value));
+        }
+        for low in -1..102 {
+            let expected = sorted.iter().filter(|&&x| low <= x && x < low + 10).count();
+            assert_eq!(tree.count_range(low..low + 10), expected);
+        }
+    }
+}
+
+#[test]
+fn range() {
+    let tree: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(tree.range(..).next(), None);
+
+    let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 2, 5, 3, 6, 8, 4].into_iter().collect();
+    assert_eq!(
+        tree.range(..).collect::<Vec<&i32>>(),
+        tree.inorder().collect::<Vec<&i32>>()
+    );
+    assert_eq!(
+        tree.range(2..5).collect::<Vec<&i32>>(),
+        vec![&2, &3, &4, &4]
+    );
+    assert_eq!(tree.range(4..=4).collect::<Vec<&i32>>(), vec![&4, &4]);
+    assert_eq!(tree.range(..=1).collect::<Vec<&i32>>(), vec![&0, &1]);
+    assert_eq!(tree.range(7..).collect::<Vec<&i32>>(), vec![&7, &8]);
+    assert_eq!(tree.range(9..).next(), None);
+    assert_eq!(
+        tree.range((Bound::Excluded(4), Bound::Excluded(7)))
+            .collect::<Vec<&i32>>(),
+        vec![&5, &6]
+    );
+
+    // Double-ended and exact-size
+    let mut range = tree.range(1..7);
+    assert_eq!(range.len(), 7);
+    assert_eq!(range.next(), Some(&1));
+    assert_eq!(range.next_back(), Some(&6));
+    assert_eq!(range.next_back(), Some(&5));
+    assert_eq!(range.len(), 4);
+    assert_eq!(range.collect::<Vec<&i32>>(), vec![&2, &3, &4, &4]);
+    assert_eq!(
+        tree.range(3..).rev().collect::<Vec<&i32>>(),
+        vec![&8, &7, &6, &5, &4, &4, &3]
+    );
+}
+
+#[test]
+fn range_matches_filter() {
+    for mut tree in [
+        BinarySearchTree::new_unbalanced(),
+        BinarySearchTree::new_balanced(),
+    ] {
+        for i in 0..300 {
+            tree.insert((i * 37) % 101);
+        }
+
+        let sorted: Vec<&i32> = tree.inorder().collect();
+        for low in -1..102 {
+            let expected: Vec<&i32> = sorted
+                .iter()
+                .copied()
+                .filter(|&&x| low <= x && x <= low + 7)
+                .collect();
+            assert_eq!(tree.range(low..=low + 7).collect::<Vec<&i32>>(), expected);
+
+            let mut reversed: Vec<&i32> = tree.range(low..=low + 7).rev().collect();
+            reversed.reverse();
+            assert_eq!(reversed, expected);
+        }
+    }
+}
+
+#[test]
+fn map_basics() {
+    let mut map: BinarySearchMap<i32, &str> = BinarySearchMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.get(&1), None);
+    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.first_key_value(), None);
+    assert_eq!(map.last_key_value(), None);
+
+    assert_eq!(map.insert(25, "a"), None);
+    assert_eq!(map.insert(15, "b"), None);
+    assert_eq!(map.insert(40, "c"), None);
+    assert_eq!(map.insert(10, "d"), None);
+    assert_eq!(map.insert(15, "e"), Some("b"));
+    assert_eq!(map.len(), 4);
+
+    assert_eq!(map.get(&15), Some(&"e"));
+    assert_eq!(map.get(&16), None);
+    assert!(map.contains_key(&40));
+    assert_eq!(map.get_key_value(&10), Some((&10, &"d")));
+
+    *map.get_mut(&40).unwrap() = "f";
+    assert_eq!(map.get(&40), Some(&"f"));
+    assert_eq!(map.get_mut(&41), None);
+
+    assert_eq!(map.first_key_value(), Some((&10, &"d")));
+    assert_eq!(map.last_key_value(), Some((&40, &"f")));
+    assert_eq!(
+        map.iter().collect::<Vec<(&i32, &&str)>>(),
+        vec![(&10, &"d"), (&15, &"e"), (&25, &"a"), (&40, &"f")]
+    );
+    assert_eq!(
+        map.keys().rev().collect::<Vec<&i32>>(),
+        vec![&40, &25, &15, &10]
+    );
+    assert_eq!(map.values().len(), 4);
+
+    assert_eq!(map.remove(&15), Some("e"));
+    assert_eq!(map.remove(&15), None);
+    assert_eq!(map.pop_first(), Some((10, "d")));
+    assert_eq!(map.pop_last(), Some((40, "f")));
+    assert_eq!(map.len(), 1);
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.iter().next(), None);
+}
+
+#[test]
+fn map_successor_and_predecessor() {
+    let map: BinarySearchMap<i32, i32> = vec![25, 15, 40, 10, 18, 45, 35]
+        .into_iter()
+        .map(|key| (key, key * 10))
+        .collect();
+
+    assert_eq!(map.successor(&25), Some((&35, &350)));
+    assert_eq!(map.successor(&20), Some((&25, &250)));
+    assert_eq!(map.successor(&45), None);
+    assert_eq!(map.predecessor(&25), Some((&18, &180)));
+    assert_eq!(map.predecessor(&11), Some((&10, &100)));
+    assert_eq!(map.predecessor(&10), None);
+}
+
+#[test]
+fn map_matches_set() {
+    for (mut map, mut set) in [
+        (
+            BinarySearchMap::new_unbalanced(),
+            BinarySearchTree::new_unbalanced(),
+        ),
+        (
+            BinarySearchMap::new_balanced(),
+            BinarySearchTree::new_balanced(),
+        ),
+    ] {
+        for i in 0..300 {
+            let key = (i * 37) % 101;
+            assert_eq!(map.insert(key, i).is_some(), set.insert_without_dup(key));
+        }
+        for i in 0..100 {
+            let key = (i * 53) % 107;
+            assert_eq!(map.remove(&key).is_some(), set.remove(&key));
+        }
+
+        assert_eq!(map.len(), set.len());
+        assert_eq!(map.keys().collect::<Vec<&i32>>(), set.sorted_vec());
+        assert_eq!(map.first_key_value().map(|(key, _)| key), set.min());
+        assert_eq!(map.last_key_value().map(|(key, _)| key), set.max());
+    }
+
+    let first: BinarySearchMap<i32, i32> = (0..10).map(|i| (i, i)).collect();
+    let second: BinarySearchMap<i32, i32> = (0..10).rev().map(|i| (i, i)).collect();
+    assert!(first == second);
+}
+
+#[test]
+fn map_entry() {
+    let mut map: BinarySearchMap<String, usize> = BinarySearchMap::new_balanced();
+    let text = "the quick brown fox jumps over the lazy dog the end";
+    for word in text.split(' ') {
+        *map.entry(word.to_string()).or_insert(0) += 1;
+    }
+    assert_eq!(map.len(), 9);
+    assert_eq!(map.get(&"the".to_string()), Some(&3));
+    assert_eq!(map.get(&"fox".to_string()), Some(&1));
+
+    // or_insert_with and or_default only build a value for vacant entries
+    assert_eq!(*map.entry("fox".to_string()).or_insert_with(|| 100), 1);
+    assert_eq!(*map.entry("cat".to_string()).or_insert_with(|| 100), 100);
+    assert_eq!(*map.entry("emu".to_string()).or_default(), 0);
+    assert_eq!(
+        *map.entry("owl".to_string())
+            .or_insert_with_key(|key| key.len()),
+        3
+    );
+
+    map.entry("dog".to_string())
+        .and_modify(|count| *count += 10);
+    map.entry("yak".to_string())
+        .and_modify(|count| *count += 10);
+    assert_eq!(map.get(&"dog".to_string()), Some(&11));
+    assert_eq!(map.get(&"yak".to_string()), None);
+
+    match map.entry("quick".to_string()) {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), "quick");
+            assert_eq!(entry.get(), &1);
+            assert_eq!(entry.insert(5), 1);
+            assert_eq!(entry.remove_entry(), ("quick".to_string(), 5));
+        }
+        Entry::Vacant(_) => panic!("expected an occupied entry"),
+    }
+    match map.entry("quick".to_string()) {
+        Entry::Occupied(_) => panic!("expected a vacant entry"),
+        Entry::Vacant(entry) => assert_eq!(entry.into_key(), "quick"),
+    }
+    assert_eq!(map.get(&"quick".to_string()), None);
+    assert_eq!(map.len(), 11);
+    assert_eq!(
+        map.keys().map(String::as_str).collect::<Vec<&str>>(),
+        vec![
+            "brown", "cat", "dog", "emu", "end", "fox", "jumps", "lazy", "over", "owl", "the"
+        ]
+    );
+}
+
+#[test]
+fn map_entry_matches_insert() {
+    for (mut by_entry, mut by_insert) in [
+        (
+            BinarySearchMap::new_unbalanced(),
+            BinarySearchMap::new_unbalanced(),
+        ),
+        (
+            BinarySearchMap::new_balanced(),
+            BinarySearchMap::new_balanced(),
+        ),
+    ] {
+        for i in 0..500 {
+            let key = (i * 37) % 101;
+            *by_entry.entry(key).or_insert(0) += i;
+            let sum = by_insert.get(&key).copied().unwrap_or(0) + i;
+            by_insert.insert(key, sum);
+
+            let key = (i * 53) % 107;
+            if let Entry::Occupied(entry) = by_entry.entry(key) {
+                entry.remove();
+            }
+            by_insert.remove(&key);
+        }
+
+        assert_eq!(by_entry.len(), by_insert.len());
+        assert!(by_entry == by_insert);
+        if by_entry.balanced {
+            assert_avl(&by_entry.root);
+        }
+    }
+}
+
+#[test]
+fn iter() {
+    let tree: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(tree.iter().len(), 0);
+    assert_eq!(tree.iter().next_back(), None);
+
+    let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 2, 5, 3, 6, 8].into_iter().collect();
+    let mut iter = tree.iter();
+    assert_eq!(iter.len(), 9);
+    assert_eq!(iter.next(), Some(&0));
+    assert_eq!(iter.next_back(), Some(&8));
+    assert_eq!(iter.next_back(), Some(&7));
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.rev().collect::<Vec<&i32>>(), vec![&6, &5, &4, &3, &2]);
+
+    let mut elements = Vec::new();
+    for element in &tree {
+        elements.push(*element);
+    }
+    assert_eq!(elements, (0..=8).collect::<Vec<i32>>());
+}
+
+#[test]
+fn traversal_types() {
+    // Every traversal has a type that can be named outside the module
+    struct Traversals<'a> {
+        inorder: Iter<'a, i32>,
+        reverse_order: Rev<Iter<'a, i32>>,
+        preorder: Preorder<'a, i32>,
+        postorder: Postorder<'a, i32>,
+        level_order: LevelOrder<'a, i32>,
+    }
+
+    let tree: BinarySearchTree<i32> = vec![2, 1, 3].into_iter().collect();
+    let traversals = Traversals {
+        inorder: tree.inorder(),
+        reverse_order: tree.reverse_order(),
+        preorder: tree.preorder(),
+        postorder: tree.postorder(),
+        level_order: tree.level_order(),
+    };
+    assert_eq!(traversals.inorder.collect::<Vec<&i32>>(), [&1, &2, &3]);
+    assert_eq!(
+        traversals.reverse_order.collect::<Vec<&i32>>(),
+        [&3, &2, &1]
+    );
+    assert_eq!(traversals.preorder.collect::<Vec<&i32>>(), [&2, &1, &3]);
+    assert_eq!(traversals.postorder.collect::<Vec<&i32>>(), [&1, &3, &2]);
+    assert_eq!(traversals.level_order.collect::<Vec<&i32>>(), [&2, &1, &3]);
+}
+
+#[test]
+fn into_iter() {
+    let tree: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(tree.into_iter().next(), None);
+
+    let tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 2, 5, 3, 6, 8, 4].into_iter().collect();
+    assert_eq!(
+        tree.clone().into_iter().collect::<Vec<i32>>(),
+        vec![0, 1, 2, 3, 4, 4, 5, 6, 7, 8]
+    );
+    assert_eq!(
+        tree.clone().into_iter().rev().collect::<Vec<i32>>(),
+        vec![8, 7, 6, 5, 4, 4, 3, 2, 1, 0]
+    );
+
+    // Alternating between both ends
+    let mut iter = tree.into_iter();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    while iter.len() > 0 {
+        front.extend(iter.next());
+        back.extend(iter.next_back());
+    }
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(front, vec![0, 1, 2, 3, 4]);
+    assert_eq!(back, vec![8, 7, 6, 5, 4]);
+
+    // Partially consumed iterators drop their remaining nodes
+    let tree: BinarySearchTree<String> = (0..100).map(|i| i.to_string()).collect();
+    let mut iter = tree.into_iter();
+    assert_eq!(iter.next(), Some("0".to_string()));
+    assert_eq!(iter.next_back(), Some("99".to_string()));
+    assert_eq!(iter.len(), 98);
+
+    for mut tree in [
+        BinarySearchTree::new_unbalanced(),
+        BinarySearchTree::new_balanced(),
+    ] {
+        for i in 0..300 {
+            tree.insert((i * 37) % 101);
+        }
+        let expected: Vec<i32> = tree.iter().copied().collect();
+        let mut iter = tree.into_iter();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        for step in 0.. {
+            let element = if step % 3 == 0 {
+                iter.next_back()
+            } else {
+                iter.next()
+            };
+            match element {
+                Some(element) if step % 3 == 0 => back.push(element),
+                Some(element) => front.push(element),
+                None => break,
+            }
+        }
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, expected);
+    }
+}
+
+#[test]
+fn set_algebra() {
+    let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+    let a: BinarySearchTree<i32> = vec![5, 1, 9, 3, 7, 11].into_iter().collect();
+    let b: BinarySearchTree<i32> = vec![6, 3, 9, 12, 0].into_iter().collect();
+
+    assert_eq!(
+        a.union(&b).collect::<Vec<&i32>>(),
+        vec![&0, &1, &3, &5, &6, &7, &9, &11, &12]
+    );
+    assert_eq!(a.intersection(&b).collect::<Vec<&i32>>(), vec![&3, &9]);
+    assert_eq!(
+        a.difference(&b).collect::<Vec<&i32>>(),
+        vec![&1, &5, &7, &11]
+    );
+    assert_eq!(b.difference(&a).collect::<Vec<&i32>>(), vec![&0, &6, &12]);
+    assert_eq!(
+        a.symmetric_difference(&b).collect::<Vec<&i32>>(),
+        vec![&0, &1, &5, &6, &7, &11, &12]
+    );
+
+    assert_eq!(a.union(&empty).collect::<Vec<&i32>>(), a.sorted_vec());
+    assert_eq!(empty.union(&a).collect::<Vec<&i32>>(), a.sorted_vec());
+    assert_eq!(a.intersection(&empty).next(), None);
+    assert_eq!(a.difference(&empty).collect::<Vec<&i32>>(), a.sorted_vec());
+    assert_eq!(empty.difference(&a).next(), None);
+    assert_eq!(
+        empty.symmetric_difference(&a).collect::<Vec<&i32>>(),
+        a.sorted_vec()
+    );
+
+    // Duplicates follow multiset semantics
+    let c: BinarySearchTree<i32> = vec![1, 1, 1, 2].into_iter().collect();
+    let d: BinarySearchTree<i32> = vec![1, 2, 2].into_iter().collect();
+    assert_eq!(c.union(&d).collect::<Vec<&i32>>(), vec![&1, &1, &1, &2, &2]);
+    assert_eq!(c.intersection(&d).collect::<Vec<&i32>>(), vec![&1, &2]);
+    assert_eq!(c.difference(&d).collect::<Vec<&i32>>(), vec![&1, &1]);
+    assert_eq!(
+        c.symmetric_difference(&d).collect::<Vec<&i32>>(),
+        vec![&1, &1, &2]
+    );
+}
+
+#[test]
+fn subset_superset_disjoint() {
+    let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+    let small: BinarySearchTree<i32> = vec![3, 9].into_iter().collect();
+    let large: BinarySearchTree<i32> = vec![5, 1, 9, 3, 7, 11].into_iter().collect();
+    let other: BinarySearchTree<i32> = vec![2, 4, 6].into_iter().collect();
+
+    assert!(empty.is_subset(&large));
+    assert!(small.is_subset(&large));
+    assert!(large.is_subset(&large));
+    assert!(!large.is_subset(&small));
+    assert!(!other.is_subset(&large));
+
+    assert!(large.is_superset(&small));
+    assert!(large.is_superset(&empty));
+    assert!(!small.is_superset(&large));
+
+    assert!(large.is_disjoint(&other));
+    assert!(empty.is_disjoint(&empty));
+    assert!(!large.is_disjoint(&small));
+
+    let twice: BinarySearchTree<i32> = vec![3, 3].into_iter().collect();
+    assert!(!twice.is_subset(&small));
+    assert!(small.is_superset(&vec![3].into_iter().collect()));
+}
+
+#[test]
+fn split_off() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert!(tree.split_off(&0).is_empty());
+
+    let mut tree: BinarySearchTree<i32> = vec![7, 1, 0, 4, 2, 5, 3, 6, 8, 4].into_iter().collect();
+    let larger = tree.split_off(&4);
+    assert_eq!(tree.sorted_vec(), vec![&0, &1, &2, &3]);
+    assert_eq!(tree.len(), 4);
+    assert_eq!(larger.sorted_vec(), vec![&4, &4, &5, &6, &7, &8]);
+    assert_eq!(larger.len(), 6);
+    assert_eq!(larger.select(2), Some(&5));
+
+    let mut tree: BinarySearchTree<i32> = (0..10).collect();
+    assert_eq!(tree.split_off(&-1).len(), 10);
+    assert!(tree.is_empty());
+
+    let mut tree: BinarySearchTree<i32> = (0..10).collect();
+    assert!(tree.split_off(&10).is_empty());
+    assert_eq!(tree.len(), 10);
+}
+
+#[test]
+fn balanced_split_off() {
+    for key in [0, 1, 250, 511, 512, 700, 1022, 1023] {
+        let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+        tree.extend(0..1023);
+        let larger = tree.split_off(&key);
+
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<i32>>(),
+            (0..key).collect::<Vec<i32>>()
+        );
+        assert_eq!(
+            larger.iter().copied().collect::<Vec<i32>>(),
+            (key..1023).collect::<Vec<i32>>()
+        );
+        assert_avl(&tree.root);
+        assert_avl(&larger.root);
+    }
+}
+
+#[test]
+fn append() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new();
+    let mut other: BinarySearchTree<i32> = (0..5).collect();
+    tree.append(&mut other);
+    assert_eq!(tree.sorted_vec(), vec![&0, &1, &2, &3, &4]);
+    assert!(other.is_empty());
+
+    tree.append(&mut other);
+    assert_eq!(tree.len(), 5);
+
+    // Other tree after this one
+    let mut other: BinarySearchTree<i32> = (4..8).collect();
+    tree.append(&mut other);
+    assert_eq!(tree.sorted_vec(), vec![&0, &1, &2, &3, &4, &4, &5, &6, &7]);
+    assert_eq!(tree.len(), 9);
+
+    // Other tree before this one
+    let mut other: BinarySearchTree<i32> = (-3..0).collect();
+    tree.append(&mut other);
+    assert_eq!(tree.min(), Some(&-3));
+    assert_eq!(tree.len(), 12);
+    assert_eq!(tree.select(3), Some(&0));
+
+    // Overlapping ranges
+    let mut other: BinarySearchTree<i32> = vec![-10, 2, 20].into_iter().collect();
+    tree.append(&mut other);
+    assert_eq!(tree.len(), 15);
+    assert_eq!(
+        tree.sorted_vec(),
+        vec![
+            &-10, &-3, &-2, &-1, &0, &1, &2, &2, &3, &4, &4, &5, &6, &7, &20
+        ]
+    );
+}
+
+#[test]
+fn balanced_split_and_append_round_trip() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend((0..2000).map(|i| (i * 37) % 1009));
+
+    let mut pieces = Vec::new();
+    for key in [900, 700, 500, 300, 100] {
+        pieces.push(tree.split_off(&key));
+    }
+    pieces.push(tree);
+
+    let mut joined: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    while let Some(mut piece) = pieces.pop() {
+        assert_avl(&piece.root);
+        joined.append(&mut piece);
+        assert_avl(&joined.root);
+    }
+
+    assert_eq!(joined.len(), 2000);
+    let mut expected: Vec<i32> = (0..2000).map(|i| (i * 37) % 1009).collect();
+    expected.sort();
+    assert_eq!(joined.into_iter().collect::<Vec<i32>>(), expected);
+}
+
+#[test]
+fn append_mixed_modes() {
+    for (before, after) in [(0..1000, 1000..1100), (1000..1100, 0..1000)] {
+        let mut balanced: BinarySearchTree<i32> = before.clone().collect();
+        let mut unbalanced: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+        unbalanced.extend(after.clone());
+        balanced.append(&mut unbalanced);
+        assert!(balanced.validate().is_valid());
+        assert!(balanced.is_balanced());
+        assert_avl(&balanced.root);
+
+        balanced.extend(2000..2100);
+        assert_avl(&balanced.root);
+        assert_eq!(balanced.len(), 1200);
+
+        // An unbalanced tree takes any shape, so a balanced one is joined as is
+        let mut unbalanced: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+        unbalanced.extend(after.clone());
+        let mut balanced: BinarySearchTree<i32> = before.collect();
+        unbalanced.append(&mut balanced);
+        assert!(unbalanced.validate().is_valid());
+        assert_eq!(unbalanced.len(), 1100);
+    }
+}
+
+#[test]
+fn cursor() {
+    let tree: BinarySearchTree<i32> = vec![4, 2, 6, 1, 3, 5, 7].into_iter().collect();
+
+    let mut cursor = tree.cursor_front();
+    let mut forward = Vec::new();
+    while let Some(value) = cursor.current() {
+        assert_eq!(cursor.index(), Some(forward.len()));
+        forward.push(value);
+        cursor.move_next();
+    }
+    assert_eq!(forward, tree.sorted_vec());
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.peek_next(), Some(&1));
+    assert_eq!(cursor.peek_prev(), Some(&7));
+
+    let mut cursor = tree.cursor_back();
+    let mut backward = Vec::new();
+    while let Some(value) = cursor.current() {
+        backward.push(value);
+        cursor.move_prev();
+    }
+    backward.reverse();
+    assert_eq!(backward, tree.sorted_vec());
+
+    // Moving from the ghost position wraps around
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&7));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&1));
+
+    let cursor = tree.cursor_at(&4);
+    assert_eq!(cursor.index(), Some(3));
+    assert_eq!(cursor.parent(), None);
+    assert_eq!(cursor.left_child(), Some(&2));
+    assert_eq!(cursor.right_child(), Some(&6));
+    assert_eq!(cursor.peek_prev(), Some(&3));
+    assert_eq!(cursor.peek_next(), Some(&5));
+
+    let cursor = tree.cursor_at(&5);
+    assert_eq!(cursor.parent(), Some(&6));
+    assert_eq!(cursor.left_child(), None);
+    assert_eq!(tree.cursor_at(&8).current(), None);
+
+    let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+    let mut cursor = empty.cursor_front();
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+}
+
+#[test]
+fn cursor_mut() {
+    let mut tree: BinarySearchTree<i32> = (0..10).collect();
+
+    // Remove while walking
+    let mut cursor = tree.cursor_front_mut();
+    while let Some(&value) = cursor.current() {
+        if value % 3 == 0 {
+            assert_eq!(cursor.remove_current(), Some(value));
+        } else {
+            cursor.move_next();
+        }
+    }
+    assert_eq!(cursor.remove_current(), None);
+    drop(cursor);
+    assert_eq!(tree.sorted_vec(), vec![&1, &2, &4, &5, &7, &8]);
+    assert_eq!(tree.len(), 6);
+
+    let mut cursor = tree.cursor_at_mut(&4);
+    assert_eq!(cursor.insert_before(3), Ok(()));
+    assert_eq!(cursor.insert_after(6), Err(6));
+    assert_eq!(cursor.insert_after(4), Ok(()));
+    assert_eq!(cursor.insert_before(0), Err(0));
+    assert_eq!(cursor.current(), Some(&4));
+    assert_eq!(cursor.index(), Some(3));
+    assert_eq!(cursor.peek_prev(), Some(&3));
+    assert_eq!(cursor.peek_next(), Some(&4));
+
+    // On the ghost position, insert at either end
+    cursor.move_prev();
+    cursor.move_prev();
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.insert_before(9), Ok(()));
+    assert_eq!(cursor.insert_after(0), Ok(()));
+    assert_eq!(cursor.insert_after(1), Err(1));
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&0));
+    drop(cursor);
+
+    assert_eq!(
+        tree.sorted_vec(),
+        vec![&0, &1, &2, &3, &4, &4, &5, &7, &8, &9]
+    );
+    assert_eq!(tree.len(), 10);
+
+    let mut cursor = tree.cursor_back_mut();
+    assert_eq!(cursor.current(), Some(&9));
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+}
+
+#[test]
+fn balanced_cursor_mut() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(0..500);
+
+    let mut cursor = tree.cursor_front_mut();
+    while let Some(&value) = cursor.current() {
+        if value % 2 == 0 {
+            cursor.remove_current();
+        } else {
+            cursor.move_next();
+        }
+    }
+    drop(cursor);
+    assert_avl(&tree.root);
+    assert_eq!(tree.len(), 250);
+
+    let mut cursor = tree.cursor_front_mut();
+    while let Some(&value) = cursor.current() {
+        assert_eq!(cursor.insert_before(value - 1), Ok(()));
+        cursor.move_next();
+    }
+    drop(cursor);
+    assert_avl(&tree.root);
+    assert_eq!(
+        tree.sorted_vec(),
+        (0..500).collect::<Vec<_>>().iter().collect::<Vec<_>>()
+    );
+
+    // Parent and children follow the shape of the tree
+    let root = tree.root().copied().unwrap();
+    let cursor = tree.cursor_at(&root);
+    let node = tree.root.0.as_ref().unwrap();
+    assert_eq!(cursor.parent(), None);
+    assert_eq!(
+        cursor.left_child(),
+        node.left.0.as_ref().map(|left| &left.value)
+    );
+    assert_eq!(
+        cursor.right_child(),
+        node.right.0.as_ref().map(|right| &right.value)
+    );
+
+    let mut cursor = tree.cursor_at_mut(&root);
+    cursor.move_next();
+    let cursor = cursor.as_cursor();
+    assert_eq!(cursor.current(), Some(&(root + 1)));
+    assert_eq!(cursor.left_child(), None);
+}
+
+/// Everything a cursor reads at its position
+type CursorReads = [Option<i32>; 6];
+
+fn cursor_reads(cursor: &Cursor<'_, i32>) -> CursorReads {
+    [
+        cursor.current(),
+        cursor.parent(),
+        cursor.left_child(),
+        cursor.right_child(),
+        cursor.peek_prev(),
+        cursor.peek_next(),
+    ]
+    .map(|value| value.copied())
+}
+
+fn cursor_mut_reads(cursor: &CursorMut<'_, i32>) -> CursorReads {
+    [
+        cursor.current(),
+        cursor.parent(),
+        cursor.left_child(),
+        cursor.right_child(),
+        cursor.peek_prev(),
+        cursor.peek_next(),
+    ]
+    .map(|value| value.copied())
+}
+
+#[test]
+fn cursor_mut_matches_cursor() {
+    for balanced in [false, true] {
+        let mut tree: BinarySearchTree<i32> = if balanced {
+            BinarySearchTree::new()
+        } else {
+            BinarySearchTree::new_unbalanced()
+        };
+        tree.extend((0..300).map(|i| (i * 37) % 101));
+
+        for round in 0..3 {
+            // Walk both cursors all the way round in each direction
+            let mut expected = Vec::new();
+            let mut cursor = tree.cursor_back();
+            cursor.move_next();
+            for _ in 0..=tree.len() {
+                cursor.move_next();
+                expected.push((cursor.index(), cursor_reads(&cursor)));
+            }
+            for _ in 0..=tree.len() {
+                cursor.move_prev();
+                expected.push((cursor.index(), cursor_reads(&cursor)));
+            }
+
+            let len = tree.len();
+            let mut cursor = tree.cursor_back_mut();
+            cursor.move_next();
+            let mut walked = Vec::new();
+            for _ in 0..=len {
+                cursor.move_next();
+                walked.push((cursor.index(), cursor_mut_reads(&cursor)));
+            }
+            for step in 0..=len {
+                cursor.move_prev();
+                if step % 7 == 0 {
+                    // A read-only view puts the tree back together for a while
+                    let index = cursor.index();
+                    let reads = cursor_reads(&cursor.as_cursor());
+                    assert_eq!(cursor.index(), index);
+                    assert_eq!(cursor_mut_reads(&cursor), reads);
+                }
+                walked.push((cursor.index(), cursor_mut_reads(&cursor)));
+            }
+            assert_eq!(walked, expected);
+
+            // Edit along the way before comparing again
+            cursor.move_next();
+            while let Some(&value) = cursor.current() {
+                if value % 3 == round {
+                    assert_eq!(cursor.remove_current(), Some(value));
+                } else {
+                    assert_eq!(cursor.insert_after(value), Ok(()));
+                    cursor.move_next();
+                    cursor.move_next();
+                }
+            }
+            drop(cursor);
+            assert!(tree.validate().is_valid());
+            if balanced {
+                assert_avl(&tree.root);
+            }
+        }
+    }
+}
+
+#[test]
+fn from_sorted_iter() {
+    for len in 0..70 {
+        let tree = BinarySearchTree::from_sorted_iter(0..len).unwrap();
+        assert_avl(&tree.root);
+        assert_eq!(tree.len(), len as usize);
+        assert_eq!(
+            tree.sorted_vec(),
+            (0..len).collect::<Vec<_>>().iter().collect::<Vec<_>>()
+        );
+        // Complete trees are as short as possible
+        assert_eq!(
+            tree.root.height(),
+            (len as usize + 1).next_power_of_two().trailing_zeros() as usize
+        );
+    }
+
+    let tree = BinarySearchTree::from_sorted_iter(vec![1, 1, 2, 2, 2]).unwrap();
+    assert_eq!(tree.sorted_vec(), vec![&1, &1, &2, &2, &2]);
+    assert!(BinarySearchTree::from_sorted_iter(vec![1, 3, 2]).is_none());
+
+    let tree = BinarySearchTree::from_sorted_iter(0..100_000).unwrap();
+    assert_eq!(tree.root.height(), 17);
+    assert_eq!(tree.select(12345), Some(&12345));
+}
+
+#[test]
+fn rebalance() {
+    for len in 0..70 {
+        let mut tree: BinarySearchTree<i32> = (0..len).rev().collect();
+        tree.rebalance();
+        assert_avl(&tree.root);
+        assert_eq!(tree.len(), len as usize);
+        assert_eq!(
+            tree.into_iter().collect::<Vec<i32>>(),
+            (0..len).collect::<Vec<i32>>()
+        );
+    }
+
+    let mut tree: BinarySearchTree<i32> = (0..2000).map(|i| (i * 37) % 1009).collect();
+    let expected = tree.sorted_vec().into_iter().copied().collect::<Vec<i32>>();
+    tree.rebalance();
+    assert_avl(&tree.root);
+    assert_eq!(tree.root.height(), 11);
+    assert_eq!(
+        tree.sorted_vec().into_iter().copied().collect::<Vec<i32>>(),
+        expected
+    );
+
+    // Still usable afterwards, in both modes
+    tree.insert(5000);
+    assert!(tree.remove(&0));
+    assert_eq!(tree.len(), 2000);
+
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(0..100);
+    tree.rebalance();
+    tree.extend(100..200);
+    assert_avl(&tree.root);
+}
+
+/// Builds the tree that inserting `0..len` in order produces, where every
+/// node only has a right child, without the O(n^2) cost of the inserts
+fn degenerate(len: i32) -> BinarySearchTree<i32> {
+    let mut root = Tree(None);
+    for value in (0..len).rev() {
+        let height = root.height() + 1;
+        root = Tree(Some(Box::new(Node {
+            value,
+            height,
+            size: height,
+            left: Tree(None),
+            right: root,
+        })));
+    }
+
+    BinarySearchTree {
+        size: len as usize,
+        root,
+        balanced: false,
+        cmp: Natural,
+    }
+}
+
+const DEEP: i32 = 1_000_000;
+
+#[test]
+fn degenerate_drop() {
+    let tree = degenerate(DEEP);
+    assert_eq!(tree.root.height(), DEEP as usize);
+    drop(tree);
+
+    let mut tree = degenerate(DEEP);
+    tree.clear();
+    assert!(tree.is_empty());
+
+    // Partially consumed iterators still own deep subtrees
+    let mut iter = degenerate(DEEP).into_iter();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(DEEP - 1));
+    drop(iter);
+
+    let mut tree = degenerate(DEEP);
+    let larger = tree.split_off(&(DEEP / 2));
+    assert_eq!(larger.len(), (DEEP / 2) as usize);
+}
+
+#[test]
+fn degenerate_traversals() {
+    let tree = degenerate(DEEP);
+    let expected: Vec<i32> = (0..DEEP).collect();
+
+    assert!(tree.sorted_vec().into_iter().eq(expected.iter()));
+    assert!(tree.iter().rev().eq(expected.iter().rev()));
+    assert!(tree.preorder().eq(expected.iter()));
+    assert_eq!(tree.postorder().next(), Some(&(DEEP - 1)));
+    assert_eq!(tree.range(10..20).count(), 10);
+    assert_eq!(format!("{:?}", tree).len(), format!("{:?}", expected).len());
+    assert_eq!(format!("{}", tree), format!("{:?}", expected));
+
+    let mut cursor = tree.cursor_back();
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&(DEEP - 2)));
+
+    assert_eq!(tree.into_sorted_vec(), expected);
+}
+
+#[test]
+fn degenerate_clone_and_eq() {
+    let tree = degenerate(DEEP);
+    let mut clone = tree.clone();
+    assert_eq!(clone.root.height(), DEEP as usize);
+    assert!(clone == tree);
+
+    assert!(clone.remove(&(DEEP - 1)));
+    assert!(clone != tree);
+    clone.insert(DEEP);
+    assert!(clone != tree);
+
+    clone.rebalance();
+    assert_avl(&clone.root);
+    assert_eq!(clone.extract_max(), Some(DEEP));
+}
+
+#[test]
+fn retain() {
+    let mut tree: BinarySearchTree<i32> = vec![5, 2, 8, 2, 9, 1, 7].into_iter().collect();
+    tree.retain(|x| *x != 2);
+    assert_eq!(tree.sorted_vec(), vec![&1, &5, &7, &8, &9]);
+    assert_eq!(tree.len(), 5);
+    assert_avl(&tree.root);
+
+    tree.retain(|_| false);
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), None);
+
+    let mut tree = degenerate(DEEP);
+    tree.retain(|x| x % 1000 == 0);
+    assert_eq!(tree.len(), 1000);
+    assert_avl(&tree.root);
+    assert_eq!(tree.select(999), Some(&999_000));
+}
+
+#[test]
+fn drain() {
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(0..10);
+    let mut drain = tree.drain();
+    assert_eq!(drain.next(), Some(0));
+    assert_eq!(drain.next_back(), Some(9));
+    drop(drain);
+    assert!(tree.is_empty());
+
+    tree.extend(0..3);
+    assert_avl(&tree.root);
+    assert_eq!(tree.drain().collect::<Vec<i32>>(), vec![0, 1, 2]);
+    assert_eq!(tree.drain().next(), None);
+}
+
+#[test]
+fn extract_if() {
+    let mut tree: BinarySearchTree<i32> = (0..20).collect();
+    let removed: Vec<i32> = tree.extract_if(|x| x % 4 == 0).collect();
+    assert_eq!(removed, vec![0, 4, 8, 12, 16]);
+    assert_eq!(tree.len(), 15);
+    assert_avl(&tree.root);
+
+    // Stopping early keeps the elements not visited yet
+    let mut iter = tree.extract_if(|x| x % 2 == 1);
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(iter.next(), Some(3));
+    drop(iter);
+    assert_eq!(tree.len(), 13);
+    assert_eq!(
+        tree.sorted_vec(),
+        vec![&2, &5, &6, &7, &9, &10, &11, &13, &14, &15, &17, &18, &19]
+    );
+
+    // The balanced mode carries on from the rebuilt tree
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend((0..1000).rev());
+    assert_eq!(tree.extract_if(|x| *x >= 10).count(), 990);
+    tree.extend(10..500);
+    assert_avl(&tree.root);
+    assert_eq!(tree.len(), 500);
+}
+
+#[test]
+fn extract_if_panic() {
+    let mut tree: BinarySearchTree<i32> = (0..100).collect();
+    let mut removed = Vec::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        for value in tree.extract_if(|x| {
+            assert!(*x != 51, "predicate panicked");
+            x % 2 == 1
+        }) {
+            removed.push(value);
+        }
+    }));
+    assert!(result.is_err());
+
+    // Everything from the element the predicate panicked on onwards is kept
+    assert_eq!(removed, (1..51).step_by(2).collect::<Vec<i32>>());
+    assert_eq!(tree.len(), 75);
+    assert!(tree.validate().is_valid());
+    assert_avl(&tree.root);
+    let expected: Vec<i32> = (0..100).filter(|x| x % 2 == 0 || *x >= 51).collect();
+    assert_eq!(tree.into_iter().collect::<Vec<i32>>(), expected);
+}
+
+#[test]
+fn with_comparator() {
+    let mut tree = BinarySearchTree::with_comparator(|a: &i32, b: &i32| b.cmp(a));
+    tree.extend(vec![5, 1, 9, 3, 7]);
+    assert_eq!(tree.sorted_vec(), vec![&9, &7, &5, &3, &1]);
+    assert_eq!(tree.min(), Some(&9));
+    assert!(tree.contains(&3));
+    assert!(!tree.contains(&4));
+    assert_eq!(tree.successor(&5), Some(&3));
+    assert_eq!(tree.predecessor(&5), Some(&7));
+    assert_eq!(tree.rank(&5), 2);
+    assert_eq!(
+        tree.range((Bound::Included(8), Bound::Included(3)))
+            .collect::<Vec<&i32>>(),
+        vec![&7, &5, &3]
+    );
+    assert_eq!(tree.count_range(..4), 3);
+    assert_eq!(tree.count_range(4..), 2);
+
+    assert!(tree.remove(&9));
+    assert!(!tree.remove(&9));
+    assert_eq!(tree.len(), 4);
+    assert!(tree.insert_without_dup(7));
+
+    let mut larger = tree.split_off(&3);
+    assert_eq!(tree.sorted_vec(), vec![&7, &5]);
+    assert_eq!(larger.sorted_vec(), vec![&3, &1]);
+    larger.append(&mut tree);
+    assert_eq!(larger.into_sorted_vec(), vec![7, 5, 3, 1]);
+}
+
+#[test]
+fn by_key() {
+    // Case-insensitive strings
+    let mut tree = BinarySearchTree::by_key(|s: &String| s.to_lowercase());
+    tree.insert_without_dup("Banana".to_string());
+    tree.insert_without_dup("apple".to_string());
+    assert!(tree.insert_without_dup("APPLE".to_string()));
+    assert!(tree.contains(&"BANANA".to_string()));
+    assert_eq!(tree.sorted_vec(), vec!["apple", "Banana"]);
+
+    // A struct field, on a type that is not `Ord`
+    #[derive(Clone, Debug, PartialEq)]
+    struct Point {
+        x: f64,
+        id: u32,
+    }
+
+    let mut points = BinarySearchTree::by_key(|point: &Point| point.id);
+    points.extend((0..10).rev().map(|id| Point {
+        x: id as f64 / 2.0,
+        id,
+    }));
+    assert_eq!(points.min().map(|point| point.id), Some(0));
+    assert_eq!(
+        points
+            .successor(&Point { x: 0.0, id: 4 })
+            .map(|point| point.x),
+        Some(2.5)
+    );
+    assert!(points.remove(&Point { x: -1.0, id: 3 }));
+    assert_eq!(points.len(), 9);
+    assert_eq!(points.clone(), points);
+
+    let mut cursor = points.cursor_at_mut(&Point { x: 0.0, id: 4 });
+    assert_eq!(cursor.insert_before(Point { x: 1.5, id: 3 }), Ok(()));
+    assert!(cursor.insert_after(Point { x: 0.0, id: 9 }).is_err());
+    drop(cursor);
+    assert!(points.iter().map(|point| point.id).eq(0..10));
+}
+
+#[test]
+fn balanced_comparator() {
+    let descending = |a: &i32, b: &i32| b.cmp(a);
+    let mut tree = BinarySearchTree::with_comparator_balanced(descending);
+    tree.extend(0..1000);
+    assert_avl(&tree.root);
+    assert_eq!(tree.root.height(), 10);
+    assert_eq!(tree.select(0), Some(&999));
+
+    for value in (0..1000).step_by(3) {
+        assert!(tree.remove(&value));
+    }
+    assert_avl(&tree.root);
+
+    // Set algebra between trees sharing a comparator type
+    let mut other = BinarySearchTree::with_comparator(descending);
+    other.extend(vec![999, 998, 3, -1]);
+    assert_eq!(tree.intersection(&other).collect::<Vec<&i32>>(), vec![&998]);
+    assert_eq!(tree.union(&other).next(), Some(&999));
+    assert_eq!(tree.union(&other).last(), Some(&-1));
+}
+
+#[test]
+fn multiset() {
+    let mut set: BinarySearchMultiset<i32> = BinarySearchMultiset::new();
+    assert!(set.is_empty());
+    for value in [5, 3, 5, 8, 5, 3] {
+        set.insert(value);
+    }
+    assert_eq!(set.len(), 6);
+    assert_eq!(set.distinct_len(), 3);
+    assert_eq!(set.count(&5), 3);
+    assert_eq!(set.count(&4), 0);
+    assert!(set.contains(&8));
+    assert_eq!(
+        set.iter().collect::<Vec<&i32>>(),
+        vec![&3, &3, &5, &5, &5, &8]
+    );
+    assert_eq!(set.iter().next_back(), Some(&8));
+    assert_eq!(set.distinct().collect::<Vec<&i32>>(), vec![&3, &5, &8]);
+    assert_eq!(
+        set.counts().collect::<Vec<(&i32, usize)>>(),
+        vec![(&3, 2), (&5, 3), (&8, 1)]
+    );
+    assert_eq!(set.successor(&3), Some(&5));
+    assert_eq!(set.predecessor(&5), Some(&3));
+    assert_eq!(format!("{:?}", set), "{3, 3, 5, 5, 5, 8}");
+
+    assert!(set.remove_one(&5));
+    assert_eq!(set.count(&5), 2);
+    assert_eq!(set.remove_all(&5), 2);
+    assert_eq!(set.remove_all(&5), 0);
+    assert!(!set.remove_one(&5));
+    assert_eq!(set.len(), 3);
+    assert_eq!(set.distinct_len(), 2);
+    assert!(set.remove_one(&8));
+    assert_eq!(set.max(), Some(&3));
+    assert_eq!(set.min(), Some(&3));
+
+    let clone = set.clone();
+    assert_eq!(clone, set);
+    set.insert(3);
+    assert_ne!(clone, set);
+    set.clear();
+    assert!(set.is_empty());
+}
+
+#[test]
+fn multiset_keeps_paths_short() {
+    // A plain tree with duplicates grows a path per copy, a multiset does not
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    let mut set: BinarySearchMultiset<i32> = BinarySearchMultiset::new();
+    for _ in 0..500 {
+        tree.insert(7);
+        set.insert(7);
+    }
+    assert_eq!(tree.root.height(), 500);
+    assert_eq!(set.distinct_len(), 1);
+    assert_eq!(set.count(&7), 500);
+
+    let mut set = BinarySearchMultiset::new_balanced();
+    set.extend((0..3000).map(|i| i % 1000));
+    assert_eq!(set.len(), 3000);
+    assert_eq!(set.distinct_len(), 1000);
+    assert!(set.counts().all(|(_, count)| count == 3));
+    for value in 0..500 {
+        assert_eq!(set.remove_all(&value), 3);
+    }
+    assert_eq!(set.len(), 1500);
+    assert_eq!(set.min(), Some(&500));
+
+    let mut by_length =
+        BinarySearchMultiset::with_comparator(|a: &&str, b: &&str| a.len().cmp(&b.len()));
+    by_length.extend(vec!["a", "bb", "c", "dd", "e"]);
+    assert_eq!(by_length.count(&"z"), 3);
+    assert_eq!(
+        by_length.iter().collect::<Vec<_>>(),
+        vec![&"a", &"a", &"a", &"bb", &"bb"]
+    );
+}
+
+#[test]
+fn borrowed_lookups() {
+    let mut tree: BinarySearchTree<String> = ["pear", "apple", "fig"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert!(tree.contains("fig"));
+    assert!(!tree.contains("kiwi"));
+    assert_eq!(tree.get("apple").map(String::as_str), Some("apple"));
+    assert_eq!(tree.successor("fig").map(String::as_str), Some("pear"));
+    assert_eq!(tree.predecessor("b").map(String::as_str), Some("apple"));
+    assert_eq!(tree.rank("g"), 2);
+    assert!(tree.remove("pear"));
+    assert_eq!(tree.take("fig"), Some("fig".to_string()));
+    assert_eq!(tree.take("fig"), None);
+    assert_eq!(tree.len(), 1);
+}
+
+#[test]
+fn get_take_replace() {
+    // Ordered by `id` only, so lookups by id hand back the rest of the record
+    #[derive(Debug, PartialEq, Eq)]
+    struct Record {
+        id: u32,
+        name: &'static str,
+    }
+
+    impl PartialOrd for Record {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Record {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.id.cmp(&other.id)
+        }
+    }
+
+    let key = |id| Record { id, name: "" };
+    let mut tree: BinarySearchTree<Record> = BinarySearchTree::new_balanced();
+    assert_eq!(tree.replace(Record { id: 2, name: "two" }), None);
+    assert_eq!(tree.replace(Record { id: 1, name: "one" }), None);
+    assert_eq!(tree.len(), 2);
+
+    assert_eq!(tree.get(&key(2)).map(|record| record.name), Some("two"));
+    assert_eq!(
+        tree.replace(Record {
+            id: 2,
+            name: "deux"
+        }),
+        Some(Record { id: 2, name: "two" })
+    );
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree.get(&key(2)).map(|record| record.name), Some("deux"));
+
+    assert_eq!(tree.take(&key(1)), Some(Record { id: 1, name: "one" }));
+    assert_eq!(tree.take(&key(1)), None);
+    assert_eq!(tree.len(), 1);
+
+    for id in 3..200 {
+        assert_eq!(tree.replace(key(id)), None);
+    }
+    assert_avl(&tree.root);
+    assert_eq!(tree.len(), 198);
+}
+
+#[test]
+fn floor_and_ceiling() {
+    let tree: BinarySearchTree<i32> = vec![8, 3, 10, 1, 6, 14, 4, 7, 13].into_iter().collect();
+    let sorted = tree.sorted_vec();
+    for value in -1..17 {
+        assert_eq!(
+            tree.floor(&value),
+            sorted.iter().rev().find(|x| ***x <= value).copied()
+        );
+        assert_eq!(
+            tree.ceiling(&value),
+            sorted.iter().find(|x| ***x >= value).copied()
+        );
+    }
+    assert_eq!(tree.successor(&6), Some(&7));
+    assert_eq!(tree.ceiling(&6), Some(&6));
+
+    let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(empty.floor(&0), None);
+    assert_eq!(empty.ceiling(&0), None);
+    assert_eq!(empty.nearest(&0, |a, b| a.abs_diff(*b)), None);
+
+    let words: BinarySearchTree<String> = ["b", "d"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(words.ceiling("c").map(String::as_str), Some("d"));
+    assert_eq!(words.floor("c").map(String::as_str), Some("b"));
+}
+
+#[test]
+fn nearest() {
+    let tree: BinarySearchTree<i32> = vec![8, 3, 10, 1, 6, 14, 4, 7, 13].into_iter().collect();
+    let distance = |a: &i32, b: &i32| a.abs_diff(*b);
+    for value in -5..20 {
+        let expected = tree.iter().min_by_key(|x| distance(x, &value)).unwrap();
+        assert_eq!(tree.nearest(&value, distance), Some(expected));
+    }
+
+    // Distances on floats only need to be partially ordered
+    let mut times = BinarySearchTree::by_key(|t: &f64| (t * 1000.0) as i64);
+    times.extend(vec![0.5, 1.25, 3.0]);
+    assert_eq!(times.nearest(&2.0, |a, b| (a - b).abs()), Some(&1.25));
+    assert_eq!(times.nearest(&2.2, |a, b| (a - b).abs()), Some(&3.0));
+}
+
+#[test]
+fn shape_statistics() {
+    let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(empty.height(), 0);
+    assert_eq!(empty.leaf_count(), 0);
+    assert_eq!(empty.level_widths(), Vec::<usize>::new());
+    assert!(empty.is_balanced());
+    assert_eq!(empty.depth_of(&1), None);
+
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    tree.extend(vec![8, 3, 10, 1, 6, 14, 4, 7, 13]);
+    assert_eq!(tree.height(), 4);
+    assert_eq!(tree.level_widths(), [1, 2, 3, 3]);
+    assert_eq!(tree.leaf_count(), 4);
+    assert!(!tree.is_balanced());
+    assert_eq!(tree.depth_of(&8), Some(0));
+    assert_eq!(tree.depth_of(&13), Some(3));
+    assert_eq!(tree.depth_of(&7), Some(3));
+
+    // Stack safe on a chain
+    let chain = degenerate(DEEP);
+    assert_eq!(chain.height(), DEEP as usize);
+    assert_eq!(chain.leaf_count(), 1);
+    assert_eq!(chain.level_widths().len(), DEEP as usize);
+    assert_eq!(chain.depth_of(&(DEEP - 1)), Some(DEEP as usize - 1));
+    assert!(!chain.is_balanced());
+    assert!(chain.validate().is_valid());
+}
+
+#[test]
+fn validate() {
+    // Sizes stay right through every kind of change
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    let mut balanced: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    for i in 0..200 {
+        let value = (i * 37) % 101;
+        for tree in [&mut tree, &mut balanced] {
+            tree.insert_without_dup(value);
+            if i % 3 == 0 {
+                tree.insert(value);
+            }
+            if i % 5 == 0 {
+                tree.remove(&((value * 7) % 101));
+            }
+            let report = tree.validate();
+            assert!(report.is_valid(), "{:?}", report);
+        }
+    }
+    assert!(balanced.is_balanced());
+    assert_eq!(balanced.validate().height, balanced.height());
+
+    // Each kind of corruption is reported
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    tree.extend(0..10);
+    tree.size = 9;
+    let report = tree.validate();
+    assert_eq!((report.node_count, report.recorded_size), (10, 9));
+    assert!(!report.is_valid());
+    tree.size = 10;
+
+    tree.root.0.as_mut().unwrap().value = 20;
+    let report = tree.validate();
+    assert_eq!(report.order_violations, 1);
+    assert!(!report.is_valid());
+    tree.root.0.as_mut().unwrap().value = 0;
+
+    let root = tree.root.0.as_mut().unwrap();
+    root.height += 1;
+    root.size -= 1;
+    let report = tree.validate();
+    assert_eq!((report.stale_heights, report.stale_sizes), (1, 1));
+    assert_eq!(report.unbalanced_nodes, 0);
+    assert_eq!(report.height, 10);
+
+    let mut balanced = BinarySearchTree::new_balanced();
+    balanced.root = degenerate(3).root.take();
+    balanced.size = 3;
+    let report = balanced.validate();
+    assert_eq!(report.unbalanced_nodes, 1);
+    assert!(!report.is_valid());
+}
+
+#[test]
+fn render() {
+    let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(empty.to_dot(), "digraph {\n}\n");
+    assert_eq!(empty.pretty(), "");
+
+    // Single children keep their side, and labels are escaped
+    let tree: BinarySearchTree<String> = ["b", "a\"", "c", "d"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let dot = tree.to_dot();
+    assert!(dot.contains("node1 [label=\"a\\\"\"];"));
+    let edges: Vec<&str> = dot.lines().filter(|line| line.contains("->")).collect();
+    assert_eq!(
+        edges,
+        [
+            "    node0 -> node1;",
+            "    node0 -> node2;",
+            "    node2 -> null2 [style=invis];",
+            "    node2 -> node3;",
+        ]
+    );
+    assert_eq!(
+        tree.pretty().lines().collect::<Vec<&str>>(),
+        ["        ┌── d", "    ┌── c", "b", "    └── a\""]
+    );
+
+    let chain = degenerate(4);
+    assert_eq!(
+        chain.pretty().lines().collect::<Vec<&str>>(),
+        ["            ┌── 3", "        ┌── 2", "    ┌── 1", "0"]
+    );
+    // A node line, an edge and an invisible point (two lines) per node but
+    // the last, plus the opening and closing lines
+    assert_eq!(
+        degenerate(DEEP).to_dot().lines().count(),
+        4 * DEEP as usize - 1
+    );
+}
+
+/// Addresses of the nodes of a persistent tree, checking its heights, sizes
+/// and balance on the way
+fn persistent_nodes<T>(root: &Link<T>) -> HashSet<*const ()> {
+    let mut nodes = HashSet::new();
+    let mut stack = vec![root];
+    while let Some(link) = stack.pop() {
+        if let Some(node) = link {
+            let height = |link: &Link<T>| link.as_ref().map_or(0, |node| node.height);
+            let size = |link: &Link<T>| link.as_ref().map_or(0, |node| node.size);
+            let (left, right) = (height(&node.left), height(&node.right));
+            assert!(left.abs_diff(right) <= 1, "unbalanced node");
+            assert_eq!(node.height, 1 + left.max(right));
+            assert_eq!(node.size, 1 + size(&node.left) + size(&node.right));
+            nodes.insert(Arc::as_ptr(node) as *const ());
+            stack.push(&node.left);
+            stack.push(&node.right);
+        }
+    }
+    nodes
+}
+
+#[test]
+fn persistent() {
+    let empty: PersistentBinarySearchTree<i32> = PersistentBinarySearchTree::new();
+    assert!(empty.is_empty());
+    assert_eq!(empty.remove(&1).len(), 0);
+    assert_eq!((empty.min(), empty.max()), (None, None));
+
+    // Every version keeps its own elements
+    let mut versions = vec![empty];
+    for i in 0..100 {
+        let last = versions.last().unwrap();
+        let next = if i % 4 == 3 {
+            last.remove(&((i * 7) % 50))
+        } else {
+            last.insert((i * 13) % 50)
+        };
+        versions.push(next);
+    }
+    let mut model = std::collections::BTreeSet::new();
+    for (i, version) in versions.iter().enumerate().skip(1) {
+        let i = i as i32 - 1;
+        if i % 4 == 3 {
+            model.remove(&((i * 7) % 50));
+        } else {
+            model.insert((i * 13) % 50);
+        }
+        assert!(version.iter().eq(model.iter()));
+        assert_eq!(version.len(), model.len());
+        assert_eq!(version.iter().next_back(), model.iter().next_back());
+        persistent_nodes(&version.root);
+    }
+
+    // Unchanged versions are shared rather than copied
+    let last = versions.last().unwrap();
+    assert!(last.clone().ptr_eq(last));
+    assert!(last.insert(*last.min().unwrap()).ptr_eq(last));
+    assert!(last.remove(&1000).ptr_eq(last));
+    assert!(!last.insert(1000).ptr_eq(last));
+    assert_eq!(last, &last.insert(1000).remove(&1000));
+}
+
+#[test]
+fn persistent_shares_unchanged_subtrees() {
+    let tree: PersistentBinarySearchTree<i32> = (0..1 << 12).collect();
+    let old = persistent_nodes(&tree.root);
+    let height = tree.root.as_ref().unwrap().height;
+    assert!(height <= 14);
+
+    for changed in [
+        tree.insert(-1),
+        tree.insert(2000),
+        tree.remove(&2000),
+        tree.remove(&0),
+    ] {
+        let new = persistent_nodes(&changed.root);
+        let copied = new.difference(&old).count();
+        assert!(copied <= height + 2, "copied {} nodes", copied);
+    }
+    assert_eq!(tree.len(), 1 << 12);
+}
+
+#[test]
+fn persistent_across_threads() {
+    let base: PersistentBinarySearchTree<i32> = (0..100).collect();
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let version = base.clone();
+            thread::spawn(move || version.insert(100 + t).remove(&t))
+        })
+        .collect();
+
+    for (t, handle) in handles.into_iter().enumerate() {
+        let version = handle.join().unwrap();
+        let t = t as i32;
+        assert!(version.contains(&(100 + t)) && !version.contains(&t));
+        assert_eq!(version.len(), 100);
+    }
+    assert!(base.iter().copied().eq(0..100));
+}
+
+#[test]
+fn concurrent() {
+    let set: ConcurrentBinarySearchTree<i32> = ConcurrentBinarySearchTree::new();
+    assert!(set.is_empty());
+    assert!(set.insert(2) && set.insert(1) && !set.insert(2));
+    assert!(set.contains(&1) && !set.contains(&3));
+    assert!(set.remove(&1) && !set.remove(&1));
+    assert_eq!(set.len(), 1);
+    assert_eq!(format!("{:?}", set), "{2}");
+
+    (0..20).for_each(|i| {
+        set.insert(i);
+    });
+    assert_eq!(set.range(..).count(), 20);
+    assert_eq!(set.range(5..=7).collect::<Vec<i32>>(), [5, 6, 7]);
+    assert_eq!(
+        set.range((Bound::Excluded(17), Bound::Unbounded))
+            .collect::<Vec<i32>>(),
+        [18, 19]
+    );
+    assert_eq!(set.range(30..).next(), None);
+
+    let snapshot = set.snapshot();
+    set.remove(&0);
+    assert!(snapshot.contains(&0) && !set.contains(&0));
+}
+
+#[test]
+fn concurrent_stress() {
+    const WRITERS: i32 = 4;
+    const PER_WRITER: i32 = 2_000;
+
+    // Even values are inserted up front and never removed. Each writer owns
+    // the odd values congruent to its index, which it inserts and removes.
+    let set: ConcurrentBinarySearchTree<i32> = ConcurrentBinarySearchTree::new();
+    let total = WRITERS * PER_WRITER;
+    (0..total).step_by(2).for_each(|i| {
+        set.insert(i);
+    });
+
+    thread::scope(|scope| {
+        for writer in 0..WRITERS {
+            let set = &set;
+            scope.spawn(move || {
+                let mine = (0..total).filter(|i| i % 2 == 1 && (i / 2) % WRITERS == writer);
+                for i in mine.clone() {
+                    assert!(set.insert(i));
+                }
+                for i in mine.clone().filter(|i| (i / 2) % 3 == 0) {
+                    assert!(set.remove(&i));
+                    assert!(!set.contains(&i));
+                }
+                for i in mine {
+                    assert_eq!(set.contains(&i), (i / 2) % 3 != 0);
+                }
+            });
+        }
+
+        for reader in 0..WRITERS {
+            let set = &set;
+            scope.spawn(move || {
+                for round in 0..200 {
+                    let start = (reader * 97 + round * 31) % total;
+                    let scan: Vec<i32> = set.range(start..start + 100).collect();
+                    assert!(scan.windows(2).all(|pair| pair[0] < pair[1]));
+                    assert!(scan.iter().all(|i| (start..start + 100).contains(i)));
+                    let stable = (start..(start + 100).min(total)).filter(|i| i % 2 == 0);
+                    assert!(stable.clone().all(|i| scan.contains(&i)));
+                    assert!(stable.clone().all(|i| set.contains(&i)));
+                }
+            });
+        }
+    });
+
+    let expected = (0..total).filter(|i| i % 2 == 0 || (i / 2) % 3 != 0);
+    assert!(set.range(..).eq(expected.clone()));
+    assert_eq!(set.len(), expected.count());
+    persistent_nodes(&set.snapshot().root);
+}
+
+#[test]
+fn concurrent_writers_overlap() {
+    const WRITERS: i32 = 4;
+    const PER_WRITER: i32 = 25;
+
+    // A slow comparator that records how many writers compare at once
+    let active = Arc::new(AtomicUsize::new(0));
+    let most = Arc::new(AtomicUsize::new(0));
+    let (counter, record) = (Arc::clone(&active), Arc::clone(&most));
+    let set = ConcurrentBinarySearchTree::with_comparator(move |a: &i32, b: &i32| {
+        let now = counter.fetch_add(1, Ordering::SeqCst) + 1;
+        record.fetch_max(now, Ordering::SeqCst);
+        thread::sleep(Duration::from_micros(200));
+        counter.fetch_sub(1, Ordering::SeqCst);
+        a.cmp(b)
+    });
+
+    thread::scope(|scope| {
+        for writer in 0..WRITERS {
+            let set = &set;
+            scope.spawn(move || {
+                for i in 0..PER_WRITER {
+                    assert!(set.insert(i * WRITERS + writer));
+                }
+            });
+        }
+    });
+
+    // Writers build their versions side by side rather than one at a time,
+    // and none of them is lost when they swap their versions in
+    assert!(most.load(Ordering::SeqCst) > 1);
+    assert!(set.range(..).eq(0..WRITERS * PER_WRITER));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    // Written sorted, and read back from any order as a balanced tree
+    let tree: BinarySearchTree<i32> = (0..100).rev().chain(0..3).collect();
+    let json = serde_json::to_string(&tree).unwrap();
+    let loaded: BinarySearchTree<i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, tree);
+    assert_eq!(loaded.height(), 7);
+    assert!(loaded.validate().is_valid());
+
+    let loaded: BinarySearchTree<i32> = serde_json::from_str("[3,1,2,1]").unwrap();
+    assert_eq!(loaded.sorted_vec(), [&1, &1, &2, &3]);
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), "[1,1,2,3]");
+    assert!(serde_json::from_str::<BinarySearchTree<i32>>("[1,\"a\"]").is_err());
+
+    // Comparators are rebuilt with `Default`
+    #[derive(Default)]
+    struct Descending;
+    impl super::Comparator<i32> for Descending {
+        fn compare(&self, a: &i32, b: &i32) -> std::cmp::Ordering {
+            b.cmp(a)
+        }
+    }
+    let descending: BinarySearchTree<i32, Descending> = serde_json::from_str("[1,3,2]").unwrap();
+    assert_eq!(descending.sorted_vec(), [&3, &2, &1]);
+
+    // Maps keep the last value of a repeated key, like `insert`
+    let map: BinarySearchMap<String, i32> =
+        serde_json::from_str(r#"{"b": 2, "a": 1, "b": 3}"#).unwrap();
+    assert_eq!(map.get(&"b".to_string()), Some(&3));
+    assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"a":1,"b":3}"#);
+
+    let set: BinarySearchMultiset<char> = serde_json::from_str(r#"["b","a","b"]"#).unwrap();
+    assert_eq!((set.count(&'b'), set.distinct_len()), (2, 2));
+    assert_eq!(serde_json::to_string(&set).unwrap(), r#"["a","b","b"]"#);
+
+    let persistent: PersistentBinarySearchTree<i32> = serde_json::from_str("[4,2,4,1]").unwrap();
+    persistent_nodes(&persistent.root);
+    assert_eq!(serde_json::to_string(&persistent).unwrap(), "[1,2,4]");
+
+    let concurrent: ConcurrentBinarySearchTree<i32> =
+        serde_json::from_str(&serde_json::to_string(&persistent).unwrap()).unwrap();
+    assert!(concurrent.insert(3));
+    assert_eq!(serde_json::to_string(&concurrent).unwrap(), "[1,2,3,4]");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_keeps_balance() {
+    // Trees are read back balanced whichever mode wrote them, and stay so
+    let mut unbalanced: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    unbalanced.extend(0..100);
+    let json = serde_json::to_string(&unbalanced).unwrap();
+    let mut loaded: BinarySearchTree<i32> = serde_json::from_str(&json).unwrap();
+    assert!(loaded.is_balanced());
+    loaded.extend(100..1_100);
+    assert!(loaded.is_balanced());
+    assert_avl(&loaded.root);
+
+    let map: BinarySearchMap<String, i32> = (0..100).map(|i| (format!("{i:04}"), i)).collect();
+    let json = serde_json::to_string(&map).unwrap();
+    let mut loaded: BinarySearchMap<String, i32> = serde_json::from_str(&json).unwrap();
+    for i in 100..1_100 {
+        loaded.insert(format!("{i:04}"), i);
+    }
+    assert_avl(&loaded.root);
+
+    let set: BinarySearchMultiset<i32> = (0..100).collect();
+    let json = serde_json::to_string(&set).unwrap();
+    let mut loaded: BinarySearchMultiset<i32> = serde_json::from_str(&json).unwrap();
+    loaded.extend(100..1_100);
+    assert_avl(&loaded.root);
+}
+
+#[test]
+fn from_preorder_and_traversals() {
+    let empty = BinarySearchTree::<i32>::from_preorder(vec![]).unwrap();
+    assert!(empty.is_empty());
+
+    // Any insertion order, duplicates included, is reproduced
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+    tree.extend((0..500).map(|i| (i * 37) % 101));
+    let copy = BinarySearchTree::from_preorder(tree.preorder().copied()).unwrap();
+    assert_eq!(copy.shape(), tree.shape());
+    assert!(copy.validate().is_valid());
+    let copy = BinarySearchTree::from_traversals(tree.preorder().copied(), tree.inorder().copied());
+    assert_eq!(copy.unwrap().shape(), tree.shape());
+
+    // As are trees without duplicates, however they were built
+    let mut balanced: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    balanced.extend(0..300);
+    (0..300).step_by(7).for_each(|i| {
+        balanced.remove(&i);
+    });
+    let copy = BinarySearchTree::from_preorder(balanced.preorder().copied()).unwrap();
+    assert_eq!(copy.shape(), balanced.shape());
+
+    assert!(BinarySearchTree::from_preorder(vec![5, 3, 8, 4]).is_none());
+    assert!(BinarySearchTree::from_traversals(vec![2, 1], vec![1, 2, 3]).is_none());
+    assert!(BinarySearchTree::from_traversals(vec![2, 1], vec![2, 1]).is_none());
+
+    // Stack safe on chains either way
+    let chain = BinarySearchTree::from_preorder(0..DEEP).unwrap();
+    assert_eq!(chain.height(), DEEP as usize);
+    let chain = BinarySearchTree::from_preorder((0..DEEP).rev()).unwrap();
+    assert_eq!((chain.height(), chain.min()), (DEEP as usize, Some(&0)));
+}
+
+#[test]
+fn shape_encoding() {
+    let empty: BinarySearchTree<i32> = BinarySearchTree::new();
+    assert_eq!(empty.shape(), Vec::<u8>::new());
+    assert!(
+        BinarySearchTree::<i32>::from_shape(&[], vec![])
+            .unwrap()
+            .is_empty()
+    );
+
+    // Equal elements on either side, which only the shape can tell apart
+    let mut tree: BinarySearchTree<i32> = BinarySearchTree::new_balanced();
+    tree.extend(vec![1; 9]);
+    tree.extend(vec![0, 2, 1, 1]);
+    let copy = BinarySearchTree::from_shape(&tree.shape(), tree.preorder().copied()).unwrap();
+    assert_eq!(copy.pretty(), tree.pretty());
+    assert_eq!(copy.len(), 13);
+    assert!(copy.validate().is_valid());
+    let by_preorder = BinarySearchTree::from_preorder(tree.preorder().copied());
+    assert!(by_preorder.is_none_or(|copy| copy.shape() != tree.shape()));
+
+    // 2 bits a node: root with two children, then two leaves
+    let small: BinarySearchTree<i32> = vec![2, 1, 3].into_iter().collect();
+    assert_eq!(small.shape(), [0b00_00_11]);
+    // Empty, cut short, a byte too long, and set bits after the last node
+    for bad in [
+        &[][..],
+        &[0b11_11_11_11],
+        &[0b00_00_11, 0],
+        &[0b11_00_00_11],
+    ] {
+        assert!(BinarySearchTree::from_shape(bad, vec![2, 1, 3]).is_none());
+    }
+    assert!(BinarySearchTree::from_shape(&small.shape(), vec![2, 1]).is_none());
+    assert!(BinarySearchTree::from_shape(&small.shape(), vec![1, 2, 3]).is_none());
+
+    let chain = degenerate(DEEP);
+    assert_eq!(chain.shape().len(), DEEP as usize / 4);
+    let copy = BinarySearchTree::from_shape(&chain.shape(), chain.preorder().copied()).unwrap();
+    assert_eq!(copy.height(), DEEP as usize);
+}
@@ -1,26 +1,368 @@
-use super::node::Node;
-use std::cmp::Ordering;
+use super::{comparator::Comparator, node::Node};
+use crate::data_structures::traversal::InorderTraversal;
+use std::{
+    cmp::{Ordering, max},
+    iter, mem,
+};
 
 #[derive(Debug)]
-pub struct Tree<T: Ord>(pub Option<Box<Node<T>>>);
+pub struct Tree<T>(pub Option<Box<Node<T>>>);
 
-impl<T: Ord> Tree<T> {
-    pub fn insert(&mut self, value: T, allow_duplicate: bool) -> bool {
+#[derive(Clone, Copy, PartialEq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Nodes detached on the way down from the root. Rebuilding the tree from
+/// the bottom up lets every ancestor of a changed link have its height and
+/// size updated (and be rebalanced) without recursion or raw pointers.
+pub struct Path<T>(pub Vec<(Box<Node<T>>, Side)>);
+
+impl<T> Path<T> {
+    pub fn new() -> Self {
+        Path(Vec::new())
+    }
+
+    /// Detaches the child of `node` on `side`, remembers `node` and returns
+    /// the detached child so the walk can continue into it
+    pub fn descend(&mut self, mut node: Box<Node<T>>, side: Side) -> Tree<T> {
+        let child = match side {
+            Side::Left => node.left.take(),
+            Side::Right => node.right.take(),
+        };
+        self.0.push((node, side));
+        child
+    }
+
+    /// Reattaches `subtree` in place of the last detached child and fixes
+    /// every node on the way back up to the root
+    pub fn rebuild(mut self, mut subtree: Tree<T>, balance: bool) -> Tree<T> {
+        while let Some((mut node, side)) = self.0.pop() {
+            match side {
+                Side::Left => node.left = subtree,
+                Side::Right => node.right = subtree,
+            }
+            subtree = Tree(Some(node));
+            subtree.fix(balance);
+        }
+        subtree
+    }
+
+    /// Reattaches the remembered nodes into the empty `root` from the top
+    /// down, adding `grown` to the size of each, and returns the link where
+    /// the last detached child goes. Heights are left as they are.
+    pub fn relink(self, root: &mut Tree<T>, grown: usize) -> &mut Tree<T> {
+        let mut link = root;
+        for (mut node, side) in self.0 {
+            node.size += grown;
+            let node = link.0.insert(node);
+            link = match side {
+                Side::Left => &mut node.left,
+                Side::Right => &mut node.right,
+            };
+        }
+        link
+    }
+
+    /// Puts `node`, which must have no children, in place of the last
+    /// detached child, reattaches the path into the empty `root` and
+    /// returns the new value. Ancestors are fixed from the bottom up only
+    /// while their height changes; above that they just grow by one, so they
+    /// are relinked from the top down and the new value is found again
+    /// without going back to the root.
+    pub fn place(mut self, root: &mut Tree<T>, node: Box<Node<T>>, balance: bool) -> &mut T {
+        let mut subtree = Tree(Some(node));
+        // Position of the new value within `subtree`
+        let mut index = 0;
+
+        while let Some((mut parent, side)) = self.0.pop() {
+            let height = parent.height;
+            match side {
+                Side::Left => parent.left = subtree,
+                Side::Right => {
+                    index += parent.left.size() + 1;
+                    parent.right = subtree;
+                }
+            }
+            subtree = Tree(Some(parent));
+            subtree.fix(balance);
+            if subtree.height() == height {
+                break;
+            }
+        }
+
+        let link = self.relink(root, 1);
+        *link = subtree;
+        link.select_mut(index).unwrap()
+    }
+
+    /// Like `rebuild`, but each remembered node is joined with `subtree`
+    /// using the child it kept, so the subtrees may differ in height by more
+    /// than rebalancing a single node can fix
+    fn join_up(mut self, mut subtree: Tree<T>, balance: bool) -> Tree<T> {
+        while let Some((mut node, side)) = self.0.pop() {
+            subtree = match side {
+                Side::Left => {
+                    let right = node.right.take();
+                    Tree::join_with(subtree, node, right, balance)
+                }
+                Side::Right => {
+                    let left = node.left.take();
+                    Tree::join_with(left, node, subtree, balance)
+                }
+            };
+        }
+        subtree
+    }
+}
+
+/// Reads the child links written by `Tree::shape`, in the form taken by
+/// `Tree::from_preorder_links`. Returns `None` if the bytes end before the
+/// last node, or go on after it.
+pub fn links_from_shape(shape: &[u8]) -> Option<Vec<(Option<usize>, Option<usize>)>> {
+    let mut children: Vec<(Option<usize>, Option<usize>)> = Vec::new();
+    if shape.is_empty() {
+        return Some(children);
+    }
+
+    // Links still waiting for a node, the next one on top. The root waits
+    // on no parent.
+    let mut waiting: Vec<Option<(usize, Side)>> = vec![None];
+    while let Some(link) = waiting.pop() {
+        let index = children.len();
+        let bits = shape.get(index / 4)? >> (2 * (index % 4)) & 0b11;
+        match link {
+            Some((parent, Side::Left)) => children[parent].0 = Some(index),
+            Some((parent, Side::Right)) => children[parent].1 = Some(index),
+            None => {}
+        }
+        children.push((None, None));
+
+        if bits & 0b10 != 0 {
+            waiting.push(Some((index, Side::Right)));
+        }
+        if bits & 0b01 != 0 {
+            waiting.push(Some((index, Side::Left)));
+        }
+    }
+
+    let len = children.len();
+    let padding_is_clear = len.is_multiple_of(4) || shape[len / 4] >> (2 * (len % 4)) == 0;
+    (shape.len() == len.div_ceil(4) && padding_is_clear).then_some(children)
+}
+
+impl<T> Tree<T> {
+    pub fn take(&mut self) -> Tree<T> {
+        Tree(self.0.take())
+    }
+
+    pub fn height(&self) -> usize {
+        self.0.as_ref().map_or(0, |node| node.height)
+    }
+
+    fn balance_factor(&self) -> isize {
+        self.0.as_ref().map_or(0, |node| {
+            node.left.height() as isize - node.right.height() as isize
+        })
+    }
+
+    /// Number of nodes in this subtree
+    pub fn size(&self) -> usize {
+        self.0.as_ref().map_or(0, |node| node.size)
+    }
+
+    /// Number of edges between the root and the first node found for which
+    /// `compare` returns `Ordering::Equal`, searching as `get_by` does
+    pub fn depth_by<F: Fn(&T) -> Ordering>(&self, compare: F) -> Option<usize> {
         let mut current = self;
+        let mut depth = 0;
+
+        while let Some(ref node) = current.0 {
+            match compare(&node.value) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Less => current = &node.right,
+                Ordering::Equal => return Some(depth),
+            }
+            depth += 1;
+        }
+
+        None
+    }
+
+    /// Number of nodes of each level, from the root down
+    pub fn level_widths(&self) -> Vec<usize> {
+        let mut widths = Vec::new();
+        let mut level: Vec<&Node<T>> = self.0.as_deref().into_iter().collect();
+
+        while !level.is_empty() {
+            widths.push(level.len());
+            level = level
+                .iter()
+                .flat_map(|node| [node.left.0.as_deref(), node.right.0.as_deref()])
+                .flatten()
+                .collect();
+        }
+
+        widths
+    }
+
+    /// Number of nodes without children
+    pub fn leaf_count(&self) -> usize {
+        self.nodes()
+            .filter(|node| node.left.0.is_none() && node.right.0.is_none())
+            .count()
+    }
+
+    /// Whether the stored heights of the subtrees of every node differ by
+    /// at most one
+    pub fn is_balanced(&self) -> bool {
+        self.nodes()
+            .all(|node| node.left.height().abs_diff(node.right.height()) <= 1)
+    }
+
+    /// Visits every node in preorder, without recursing
+    fn nodes(&self) -> impl Iterator<Item = &Node<T>> {
+        let mut stack: Vec<&Node<T>> = self.0.as_deref().into_iter().collect();
+        iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.right.0.as_deref());
+            stack.extend(node.left.0.as_deref());
+            Some(node)
+        })
+    }
+
+    fn update(&mut self) {
+        if let Some(node) = self.0.as_mut() {
+            node.height = 1 + max(node.left.height(), node.right.height());
+            node.size = 1 + node.left.size() + node.right.size();
+        }
+    }
+
+    fn rotate_left(&mut self) {
+        let mut root = self.0.take().unwrap();
+        let mut pivot = root.right.0.take().unwrap();
+        root.right = pivot.left.take();
+        let mut left = Tree(Some(root));
+        left.update();
+        pivot.left = left;
+        self.0 = Some(pivot);
+        self.update();
+    }
+
+    fn rotate_right(&mut self) {
+        let mut root = self.0.take().unwrap();
+        let mut pivot = root.left.0.take().unwrap();
+        root.left = pivot.right.take();
+        let mut right = Tree(Some(root));
+        right.update();
+        pivot.right = right;
+        self.0 = Some(pivot);
+        self.update();
+    }
+
+    /// Updates the height and size of the root node and, if `balance` is set,
+    /// restores the AVL property with at most two rotations. Both subtrees
+    /// must already be balanced and have correct heights and sizes.
+    fn fix(&mut self, balance: bool) {
+        self.update();
+
+        if !balance {
+            return;
+        }
+
+        let balance_factor = self.balance_factor();
+
+        if balance_factor > 1 {
+            let node = self.0.as_mut().unwrap();
+            // Left-right case
+            if node.left.balance_factor() < 0 {
+                node.left.rotate_left();
+            }
+            self.rotate_right();
+        } else if balance_factor < -1 {
+            let node = self.0.as_mut().unwrap();
+            // Right-left case
+            if node.right.balance_factor() > 0 {
+                node.right.rotate_right();
+            }
+            self.rotate_left();
+        }
+    }
+
+    pub fn insert<C: Comparator<T>>(
+        &mut self,
+        value: T,
+        allow_duplicate: bool,
+        balance: bool,
+        cmp: &C,
+    ) -> bool {
+        if !balance {
+            return self.insert_unbalanced(value, allow_duplicate, cmp);
+        }
+
+        let mut path = Path::new();
+        let mut current = self.take();
         let mut is_duplicate = false;
 
-        while let Some(ref mut node) = current.0 {
-            match node.value.cmp(&value) {
-                Ordering::Greater => current = &mut node.left,
-                Ordering::Less => current = &mut node.right,
+        while let Some(node) = current.0.take() {
+            match cmp.compare(&node.value, &value) {
+                Ordering::Greater => current = path.descend(node, Side::Left),
+                Ordering::Less => current = path.descend(node, Side::Right),
+                Ordering::Equal => {
+                    if allow_duplicate {
+                        is_duplicate = true;
+                        current = path.descend(node, Side::Right);
+                    } else {
+                        *self = path.rebuild(Tree(Some(node)), balance);
+                        return true;
+                    }
+                }
+            };
+        }
+
+        *self = path.rebuild(Tree(Some(Box::new(Node::new(value)))), balance);
+
+        is_duplicate
+    }
+
+    /// Without rebalancing, an insert only adds a leaf, so no path has to be
+    /// kept: a first walk finds how deep the leaf goes (and any duplicate), and
+    /// a second one updates the height and size of each node above it
+    fn insert_unbalanced<C: Comparator<T>>(
+        &mut self,
+        value: T,
+        allow_duplicate: bool,
+        cmp: &C,
+    ) -> bool {
+        let mut current = &*self;
+        let mut depth = 0;
+        let mut is_duplicate = false;
+
+        while let Some(ref node) = current.0 {
+            match cmp.compare(&node.value, &value) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Less => current = &node.right,
                 Ordering::Equal => {
                     if allow_duplicate {
                         is_duplicate = true;
-                        current = &mut node.right;
+                        current = &node.right;
                     } else {
                         return true;
                     }
                 }
+            }
+            depth += 1;
+        }
+
+        let mut current = self;
+        while let Some(ref mut node) = current.0 {
+            node.height = max(node.height, depth + 1);
+            node.size += 1;
+            depth -= 1;
+            current = match cmp.compare(&node.value, &value) {
+                Ordering::Greater => &mut node.left,
+                _ => &mut node.right,
             };
         }
 
@@ -29,18 +371,110 @@ impl<T: Ord> Tree<T> {
         is_duplicate
     }
 
-    pub fn contains(&self, target: &T) -> bool {
+    /// Finds a stored value for which `compare` returns `Ordering::Equal`.
+    /// `compare` orders a stored value relative to the one searched for, so
+    /// lookups can be made by something other than a whole `T` (such as the
+    /// key of a map entry).
+    pub fn get_by<F: Fn(&T) -> Ordering>(&self, compare: F) -> Option<&T> {
         let mut current = self;
 
         while let Some(ref node) = current.0 {
-            match node.value.cmp(target) {
+            match compare(&node.value) {
                 Ordering::Greater => current = &node.left,
                 Ordering::Less => current = &node.right,
-                Ordering::Equal => return true,
+                Ordering::Equal => return Some(&node.value),
+            }
+        }
+
+        None
+    }
+
+    /// Mutable counterpart of `get_by`. Callers must not change the value in
+    /// a way that alters its ordering.
+    pub fn get_mut_by<F: Fn(&T) -> Ordering>(&mut self, compare: F) -> Option<&mut T> {
+        let mut current = self;
+
+        while let Some(ref mut node) = current.0 {
+            match compare(&node.value) {
+                Ordering::Greater => current = &mut node.left,
+                Ordering::Less => current = &mut node.right,
+                Ordering::Equal => return Some(&mut node.value),
+            }
+        }
+
+        None
+    }
+
+    /// Joins `left`, `middle` and `right` into one tree. Every value in
+    /// `left` must be less than or equal to `middle`'s value, which must be
+    /// less than or equal to every value in `right`. `middle`'s children are
+    /// discarded. If `balance` is set, `middle` is attached down the spine of
+    /// the taller tree, where both sides have about the same height, and the
+    /// nodes above it are rebalanced; this takes O(|height difference|) time.
+    fn join_with(
+        left: Tree<T>,
+        mut middle: Box<Node<T>>,
+        right: Tree<T>,
+        balance: bool,
+    ) -> Tree<T> {
+        let mut path = Path::new();
+        let (mut left, mut right) = (left, right);
+
+        if balance {
+            while left.height() > right.height() + 1 {
+                let node = left.0.take().unwrap();
+                left = path.descend(node, Side::Right);
+            }
+            while right.height() > left.height() + 1 {
+                let node = right.0.take().unwrap();
+                right = path.descend(node, Side::Left);
+            }
+        }
+
+        middle.left = left;
+        middle.right = right;
+        let mut joined = Tree(Some(middle));
+        joined.fix(balance);
+
+        path.rebuild(joined, balance)
+    }
+
+    /// Concatenates two trees where every value in `left` is less than or
+    /// equal to every value in `right`
+    pub fn join(left: Tree<T>, mut right: Tree<T>, balance: bool) -> Tree<T> {
+        if left.0.is_none() {
+            return right;
+        }
+
+        match right.extract_min(balance) {
+            Some(min) => Tree::join_with(left, Box::new(Node::new(min)), right, balance),
+            None => left,
+        }
+    }
+
+    /// Splits off and returns the values greater than or equal to `key`,
+    /// leaving the smaller ones in this tree. Each node on the path to `key`
+    /// is joined back onto the side it belongs to, so with `balance` set both
+    /// trees stay balanced in O(height) time overall.
+    pub fn split_off<C: Comparator<T>>(&mut self, key: &T, balance: bool, cmp: &C) -> Tree<T> {
+        let mut smaller = Path::new();
+        let mut larger = Path::new();
+        let mut current = self.take();
+
+        while let Some(node) = current.0.take() {
+            if cmp.compare(&node.value, key) == Ordering::Less {
+                current = smaller.descend(node, Side::Right);
+            } else {
+                current = larger.descend(node, Side::Left);
             }
         }
 
-        false
+        *self = smaller.join_up(Tree(None), balance);
+        larger.join_up(Tree(None), balance)
+    }
+
+    pub fn contains<Q: ?Sized, C: Comparator<T, Q>>(&self, target: &Q, cmp: &C) -> bool {
+        self.get_by(|value| cmp.compare(value, target)).is_some()
     }
 
     pub fn min(&self) -> Option<&T> {
@@ -75,12 +509,13 @@ impl<T: Ord> Tree<T> {
         }
     }
 
-    pub fn successor(&self, value: &T) -> Option<&T> {
+    /// Smallest stored value for which `compare` returns `Ordering::Greater`
+    pub fn successor_by<F: Fn(&T) -> Ordering>(&self, compare: F) -> Option<&T> {
         let mut current = self.0.as_ref();
         let mut successor = None;
 
         while let Some(node) = current {
-            if node.value > *value {
+            if compare(&node.value) == Ordering::Greater {
                 successor = current;
                 current = node.left.0.as_ref();
             } else {
@@ -91,12 +526,13 @@ impl<T: Ord> Tree<T> {
         successor.map(|node| &node.value)
     }
 
-    pub fn predecessor(&self, value: &T) -> Option<&T> {
+    /// Largest stored value for which `compare` returns `Ordering::Less`
+    pub fn predecessor_by<F: Fn(&T) -> Ordering>(&self, compare: F) -> Option<&T> {
         let mut current = self.0.as_ref();
         let mut predecessor = None;
 
         while let Some(node) = current {
-            if node.value < *value {
+            if compare(&node.value) == Ordering::Less {
                 predecessor = current;
                 current = node.right.0.as_ref();
             } else {
@@ -107,96 +543,503 @@ impl<T: Ord> Tree<T> {
         predecessor.map(|node| &node.value)
     }
 
-    pub fn extract_min(&mut self) -> Option<T> {
-        let mut to_return = None;
+    /// Smallest stored value for which `compare` does not return
+    /// `Ordering::Less`
+    pub fn ceiling_by<F: Fn(&T) -> Ordering>(&self, compare: F) -> Option<&T> {
+        let mut current = self.0.as_ref();
+        let mut ceiling = None;
 
-        if self.0.is_some() {
-            let mut current = self;
+        while let Some(node) = current {
+            if compare(&node.value) == Ordering::Less {
+                current = node.right.0.as_ref();
+            } else {
+                ceiling = current;
+                current = node.left.0.as_ref();
+            }
+        }
+
+        ceiling.map(|node| &node.value)
+    }
+
+    /// Largest stored value for which `compare` does not return
+    /// `Ordering::Greater`
+    pub fn floor_by<F: Fn(&T) -> Ordering>(&self, compare: F) -> Option<&T> {
+        let mut current = self.0.as_ref();
+        let mut floor = None;
+
+        while let Some(node) = current {
+            if compare(&node.value) == Ordering::Greater {
+                current = node.left.0.as_ref();
+            } else {
+                floor = current;
+                current = node.right.0.as_ref();
+            }
+        }
+
+        floor.map(|node| &node.value)
+    }
+
+    pub fn successor<Q: ?Sized, C: Comparator<T, Q>>(&self, value: &Q, cmp: &C) -> Option<&T> {
+        self.successor_by(|stored| cmp.compare(stored, value))
+    }
+
+    pub fn predecessor<Q: ?Sized, C: Comparator<T, Q>>(&self, value: &Q, cmp: &C) -> Option<&T> {
+        self.predecessor_by(|stored| cmp.compare(stored, value))
+    }
+
+    pub fn extract_min(&mut self, balance: bool) -> Option<T> {
+        let mut path = Path::new();
+        let mut current = self.take();
 
-            while current.0.as_ref().unwrap().left.0.is_some() {
-                current = &mut current.0.as_mut().unwrap().left;
+        while let Some(node) = current.0.take() {
+            if node.left.0.is_some() {
+                current = path.descend(node, Side::Left);
+            } else {
+                let node = *node;
+                *self = path.rebuild(node.right, balance);
+                return Some(node.value);
             }
+        }
+
+        None
+    }
+
+    pub fn extract_max(&mut self, balance: bool) -> Option<T> {
+        let mut path = Path::new();
+        let mut current = self.take();
 
-            let node = current.0.take().unwrap();
-            to_return = Some(node.value);
-            current.0 = node.right.0;
+        while let Some(node) = current.0.take() {
+            if node.right.0.is_some() {
+                current = path.descend(node, Side::Right);
+            } else {
+                let node = *node;
+                *self = path.rebuild(node.left, balance);
+                return Some(node.value);
+            }
         }
 
-        to_return
+        None
     }
 
-    pub fn extract_max(&mut self) -> Option<T> {
-        let mut to_return = None;
+    pub fn remove<Q: ?Sized, C: Comparator<T, Q>>(
+        &mut self,
+        target: &Q,
+        balance: bool,
+        cmp: &C,
+    ) -> bool {
+        self.remove_by(|value| cmp.compare(value, target), balance)
+            .is_some()
+    }
 
-        if self.0.is_some() {
-            let mut current = self;
-
-            while current.0.as_ref().unwrap().right.0.is_some() {
-                current = &mut current.0.as_mut().unwrap().right;
-            }
-
-            let node = current.0.take().unwrap();
-            to_return = Some(node.value);
-            current.0 = node.left.0;
-        }
-
-        to_return
-    }
-
-    pub fn remove(&mut self, target: &T) -> bool {
-        let mut current: *mut Tree<T> = self;
-
-        unsafe {
-            while let Some(ref mut node) = (*current).0 {
-                match node.value.cmp(target) {
-                    Ordering::Greater => current = &mut node.left,
-                    Ordering::Less => current = &mut node.right,
-                    Ordering::Equal => {
-                        match (node.left.0.as_mut(), node.right.0.as_mut()) {
-                            // Node has no children
-                            (None, None) => (*current).0 = None,
-                            // Replace node with its left child
-                            (Some(_), None) => (*current).0 = node.left.0.take(),
-                            // Replace node with its right child
-                            (None, Some(_)) => (*current).0 = node.right.0.take(),
-                            // Replace vaue of current node with its successor,
-                            // then remove the successor's node
-                            (Some(_), Some(_)) => {
-                                (*current).0.as_mut().unwrap().value =
-                                    node.right.extract_min().unwrap()
-                            }
+    /// Removes and returns a stored value for which `compare` returns
+    /// `Ordering::Equal`
+    pub fn remove_by<F: Fn(&T) -> Ordering>(&mut self, compare: F, balance: bool) -> Option<T> {
+        self.remove_with(|node| compare(&node.value), balance)
+    }
+
+    /// Removes and returns the value at position `index` in sorted order
+    pub fn remove_at(&mut self, index: usize, balance: bool) -> Option<T> {
+        let mut index = index;
+        self.remove_with(|node| Self::navigate_to_index(node, &mut index), balance)
+    }
+
+    /// Moves `index` past the nodes skipped when descending from `node`
+    /// towards the value at that position, and tells which way to go
+    fn navigate_to_index(node: &Node<T>, index: &mut usize) -> Ordering {
+        let left_size = node.left.size();
+        match (*index).cmp(&left_size) {
+            Ordering::Less => Ordering::Greater,
+            Ordering::Equal => Ordering::Equal,
+            Ordering::Greater => {
+                *index -= left_size + 1;
+                Ordering::Less
+            }
+        }
+    }
+
+    /// Removes the node found by following `navigate`, which is called once
+    /// for each node on the path from the root and orders that node relative
+    /// to the one to remove
+    fn remove_with<F: FnMut(&Node<T>) -> Ordering>(
+        &mut self,
+        mut navigate: F,
+        balance: bool,
+    ) -> Option<T> {
+        let mut path = Path::new();
+        let mut current = self.take();
+
+        while let Some(mut node) = current.0.take() {
+            match navigate(&node) {
+                Ordering::Greater => current = path.descend(node, Side::Left),
+                Ordering::Less => current = path.descend(node, Side::Right),
+                Ordering::Equal => {
+                    let replacement = match (node.left.take(), node.right.take()) {
+                        // Node has no children
+                        (Tree(None), Tree(None)) => Tree(None),
+                        // Replace node with its left child
+                        (left, Tree(None)) => left,
+                        // Replace node with its right child
+                        (Tree(None), right) => right,
+                        // Replace value of current node with its successor,
+                        // then remove the successor's node
+                        (left, mut right) => {
+                            let successor = right.extract_min(balance).unwrap();
+                            let removed = mem::replace(&mut node.value, successor);
+                            node.left = left;
+                            node.right = right;
+                            let mut replacement = Tree(Some(node));
+                            replacement.fix(balance);
+                            *self = path.rebuild(replacement, balance);
+                            return Some(removed);
                         }
+                    };
 
-                        return true; // removal occurred
-                    }
+                    *self = path.rebuild(replacement, balance);
+                    return Some(node.value); // removal occurred
                 }
             }
         }
 
-        false // an element with 'target' value was not found
+        *self = path.rebuild(Tree(None), balance);
+        None // an element with 'target' value was not found
     }
 
-    pub fn sorted_vec(&self) -> Vec<&T> {
-        let mut elements = Vec::new();
+    /// Number of elements less than `target`, or less than or equal to it if
+    /// `inclusive` is set
+    pub fn rank<Q: ?Sized, C: Comparator<T, Q>>(
+        &self,
+        target: &Q,
+        inclusive: bool,
+        cmp: &C,
+    ) -> usize {
+        let mut current = self;
+        let mut rank = 0;
 
-        if let Some(node) = self.0.as_ref() {
-            elements.extend(node.left.sorted_vec());
-            elements.push(&node.value);
-            elements.extend(node.right.sorted_vec());
+        while let Some(ref node) = current.0 {
+            match cmp.compare(&node.value, target) {
+                Ordering::Less => {
+                    rank += node.left.size() + 1;
+                    current = &node.right;
+                }
+                Ordering::Equal if inclusive => {
+                    rank += node.left.size() + 1;
+                    current = &node.right;
+                }
+                _ => current = &node.left,
+            }
         }
 
-        elements
+        rank
+    }
+
+    pub fn select(&self, index: usize) -> Option<&T> {
+        let mut current = self;
+        let mut index = index;
+
+        while let Some(ref node) = current.0 {
+            match Self::navigate_to_index(node, &mut index) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Equal => return Some(&node.value),
+                Ordering::Less => current = &node.right,
+            }
+        }
+
+        None
+    }
+
+    pub fn select_mut(&mut self, index: usize) -> Option<&mut T> {
+        let mut current = self;
+        let mut index = index;
+
+        while let Some(ref mut node) = current.0 {
+            match Self::navigate_to_index(node, &mut index) {
+                Ordering::Greater => current = &mut node.left,
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Less => current = &mut node.right,
+            }
+        }
+
+        None
+    }
+
+    /// Nodes on the path from the root to the value at position `index` in
+    /// sorted order, or an empty path if there is no such value
+    pub fn path_to_index(&self, index: usize) -> Vec<&Node<T>> {
+        let mut path = Vec::new();
+        let mut current = self.0.as_deref();
+        let mut index = index;
+
+        while let Some(node) = current {
+            path.push(node);
+            match Self::navigate_to_index(node, &mut index) {
+                Ordering::Greater => current = node.left.0.as_deref(),
+                Ordering::Equal => return path,
+                Ordering::Less => current = node.right.0.as_deref(),
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Like `get_by`, but returns the position of the value found in sorted
+    /// order, or the position at which it would be inserted
+    pub fn search_by<F: Fn(&T) -> Ordering>(&self, compare: F) -> Result<usize, usize> {
+        let mut current = self;
+        let mut index = 0;
+
+        while let Some(ref node) = current.0 {
+            match compare(&node.value) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Less => {
+                    index += node.left.size() + 1;
+                    current = &node.right;
+                }
+                Ordering::Equal => return Ok(index + node.left.size()),
+            }
+        }
+
+        Err(index)
+    }
+
+    /// Inserts `value` so that it ends up at position `index` in sorted
+    /// order, without comparing it to the stored values, and returns it. The
+    /// caller must make sure that position keeps the tree ordered.
+    pub fn insert_at(&mut self, index: usize, value: T, balance: bool) -> &mut T {
+        let mut path = Path::new();
+        let mut current = self.take();
+        let mut index = index;
+
+        while let Some(node) = current.0.take() {
+            let left_size = node.left.size();
+            if index <= left_size {
+                current = path.descend(node, Side::Left);
+            } else {
+                index -= left_size + 1;
+                current = path.descend(node, Side::Right);
+            }
+        }
+
+        path.place(self, Box::new(Node::new(value)), balance)
+    }
+
+    /// Links values given in preorder into a tree, where `children[i]` holds
+    /// the indices of the left and right children of the `i`th value. Every
+    /// index must be a child of exactly one value, and greater than its
+    /// parent's, as in preorder. Children are then linked before their
+    /// parents by building from the last value back, without recursion.
+    pub fn from_preorder_links(
+        values: Vec<T>,
+        children: &[(Option<usize>, Option<usize>)],
+    ) -> Tree<T> {
+        let mut subtrees: Vec<Tree<T>> = values
+            .into_iter()
+            .map(|value| Tree(Some(Box::new(Node::new(value)))))
+            .collect();
+
+        for (index, &(left, right)) in children.iter().enumerate().rev() {
+            let left = left.map_or(Tree(None), |child| subtrees[child].take());
+            let right = right.map_or(Tree(None), |child| subtrees[child].take());
+            let subtree = &mut subtrees[index];
+            let node = subtree.0.as_mut().unwrap();
+            node.left = left;
+            node.right = right;
+            subtree.update();
+        }
+
+        subtrees.first_mut().map_or(Tree(None), Tree::take)
+    }
+
+    /// Two bits per node in preorder, four nodes to a byte starting from
+    /// the lowest bits, telling whether the node has a left child (the lower
+    /// bit) and a right child. An empty tree has no bytes.
+    pub fn shape(&self) -> Vec<u8> {
+        let mut shape = Vec::new();
+        for (index, node) in self.nodes().enumerate() {
+            if index.is_multiple_of(4) {
+                shape.push(0);
+            }
+            let bits = node.left.0.is_some() as u8 | (node.right.0.is_some() as u8) << 1;
+            *shape.last_mut().unwrap() |= bits << (2 * (index % 4));
+        }
+        shape
+    }
+
+    /// Whether the values are in order from left to right
+    pub fn is_sorted_by<C: Comparator<T>>(&self, cmp: &C) -> bool {
+        InorderTraversal::new(self.0.as_deref()).is_sorted_by(|a, b| cmp.compare(a, b).is_le())
+    }
+
+    /// Builds a perfectly balanced tree from values in sorted order, in O(n)
+    /// time. Returns `None` if the values are not sorted.
+    pub fn from_sorted<I: IntoIterator<Item = T>, C: Comparator<T>>(
+        values: I,
+        cmp: &C,
+    ) -> Option<Tree<T>> {
+        let mut vine = Tree(None);
+        let mut tail = &mut vine;
+        let mut len = 0;
+
+        for value in values {
+            tail = &mut tail.0.insert(Box::new(Node::new(value))).right;
+            len += 1;
+        }
+
+        let mut current = vine.0.as_deref();
+        while let Some(node) = current {
+            current = node.right.0.as_deref();
+            if current.is_some_and(|next| cmp.compare(&next.value, &node.value) == Ordering::Less) {
+                return None;
+            }
+        }
+
+        Some(vine.vine_to_tree(len))
+    }
+
+    /// Reshapes the tree into a perfectly balanced one with the
+    /// Day-Stout-Warren algorithm, in O(n) time. Nodes are relinked in place,
+    /// so nothing is reallocated.
+    pub fn rebalance(&mut self) {
+        let len = self.size();
+        *self = self.take().into_vine().vine_to_tree(len);
+    }
+
+    /// Turns the tree into a "vine" where every node only has a right child,
+    /// by rotating right until no node on the spine has a left child
+    pub fn into_vine(self) -> Tree<T> {
+        let mut vine = Tree(None);
+        let mut tail = &mut vine;
+        let mut rest = self;
+
+        while let Some(mut node) = rest.0.take() {
+            if let Some(mut left) = node.left.0.take() {
+                node.left = left.right.take();
+                left.right = Tree(Some(node));
+                rest = Tree(Some(left));
+            } else {
+                rest = node.right.take();
+                tail = &mut tail.0.insert(node).right;
+            }
+        }
+
+        vine
+    }
+
+    /// Folds a vine of `len` nodes into a complete tree, first making the
+    /// bottom level and then halving the spine until only the root is left
+    pub fn vine_to_tree(self, len: usize) -> Tree<T> {
+        if len == 0 {
+            return self;
+        }
+
+        // Number of nodes in the largest perfect tree with at most `len` nodes
+        let mut perfect = (1 << (len + 1).ilog2()) - 1;
+        let mut tree = self.compress(len - perfect);
+        while perfect > 1 {
+            perfect /= 2;
+            tree = tree.compress(perfect);
+        }
+        tree.update_spine()
+    }
+
+    /// Rotates left at every other node of the right spine, `count` times,
+    /// so each node passed over becomes the left child of its old right child
+    fn compress(self, count: usize) -> Tree<T> {
+        let mut tree = Tree(None);
+        let mut tail = &mut tree;
+        let mut rest = self;
+
+        for _ in 0..count {
+            let mut node = rest.0.take().unwrap();
+            let mut parent = node.right.0.take().unwrap();
+            rest = parent.right.take();
+            node.right = parent.left.take();
+            let mut left = Tree(Some(node));
+            left.update();
+            parent.left = left;
+            tail = &mut tail.0.insert(parent).right;
+        }
+
+        *tail = rest;
+        tree
+    }
+
+    /// Updates the heights and sizes of the nodes on the right spine, whose
+    /// left subtrees are already up to date
+    fn update_spine(mut self) -> Tree<T> {
+        if let Some(node) = self.0.as_mut() {
+            node.right = node.right.take().update_spine();
+        }
+        self.update();
+        self
+    }
+
+    pub fn sorted_vec(&self) -> Vec<&T> {
+        InorderTraversal::new(self.0.as_deref()).collect()
     }
 
     pub fn into_sorted_vec(self) -> Vec<T> {
-        let mut elements = Vec::new();
+        let mut elements = Vec::with_capacity(self.size());
+        let mut current = self.into_vine();
 
-        if let Some(node) = self.0 {
-            elements.extend(node.left.into_sorted_vec());
+        while let Some(mut node) = current.0.take() {
+            current = node.right.take();
             elements.push(node.value);
-            elements.extend(node.right.into_sorted_vec());
         }
 
         elements
     }
 }
+
+impl<T> Drop for Tree<T> {
+    /// Frees the nodes one at a time, rotating left children up so that no
+    /// node is dropped while it still has a subtree. Letting each `Box` drop
+    /// its children would recurse once per level of a degenerate tree.
+    fn drop(&mut self) {
+        let mut current = self.0.take();
+
+        while let Some(mut node) = current {
+            if let Some(mut left) = node.left.0.take() {
+                node.left = left.right.take();
+                left.right = Tree(Some(node));
+                current = Some(left);
+            } else {
+                current = node.right.0.take();
+            }
+        }
+    }
+}
+
+impl<T: Clone> Clone for Tree<T> {
+    /// Copies the tree node by node, keeping its shape, heights and sizes.
+    /// Subtrees are built in postorder on an explicit stack, so the depth of
+    /// the tree does not matter.
+    fn clone(&self) -> Self {
+        let mut pending = vec![(self.0.as_deref(), false)];
+        let mut built = Vec::new();
+
+        while let Some((node, children_built)) = pending.pop() {
+            let Some(node) = node else {
+                built.push(Tree(None));
+                continue;
+            };
+
+            if children_built {
+                let right = built.pop().unwrap();
+                let left = built.pop().unwrap();
+                built.push(Tree(Some(Box::new(Node {
+                    value: node.value.clone(),
+                    height: node.height,
+                    size: node.size,
+                    left,
+                    right,
+                }))));
+            } else {
+                pending.push((Some(node), true));
+                pending.push((node.right.0.as_deref(), false));
+                pending.push((node.left.0.as_deref(), false));
+            }
+        }
+
+        built.pop().unwrap()
+    }
+}
@@ -0,0 +1,96 @@
+use super::{comparator::Comparator, node::Node, tree::Tree};
+use crate::data_structures::traversal::InorderTraversal;
+use std::cmp::{Ordering, max};
+
+/// Result of `BinarySearchTree::validate`. Counts are recomputed from the
+/// nodes themselves rather than read from the bookkeeping being checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Number of nodes actually in the tree
+    pub node_count: usize,
+    /// Number of elements the tree records having
+    pub recorded_size: usize,
+    /// Number of levels actually in the tree
+    pub height: usize,
+    /// Neighbouring elements in inorder that are out of order
+    pub order_violations: usize,
+    /// Nodes whose stored height does not match their subtrees
+    pub stale_heights: usize,
+    /// Nodes whose stored size does not match their subtrees
+    pub stale_sizes: usize,
+    /// Nodes whose subtrees differ in height by more than one. Only counted
+    /// for trees that keep themselves balanced.
+    pub unbalanced_nodes: usize,
+}
+
+impl ValidationReport {
+    pub(super) fn new<T, C: Comparator<T>>(
+        root: &Tree<T>,
+        recorded_size: usize,
+        balanced: bool,
+        cmp: &C,
+    ) -> Self {
+        let mut report = ValidationReport {
+            node_count: 0,
+            recorded_size,
+            height: 0,
+            order_violations: 0,
+            stale_heights: 0,
+            stale_sizes: 0,
+            unbalanced_nodes: 0,
+        };
+
+        let inorder = || InorderTraversal::new(root.0.as_deref());
+        report.order_violations = inorder()
+            .zip(inorder().skip(1))
+            .filter(|(value, next)| cmp.compare(next, value) == Ordering::Less)
+            .count();
+
+        // Postorder on an explicit stack, with the (height, size) of each
+        // finished subtree pushed onto `computed`
+        let mut pending: Vec<(Option<&Node<T>>, bool)> = vec![(root.0.as_deref(), false)];
+        let mut computed = Vec::new();
+        while let Some((node, children_done)) = pending.pop() {
+            let Some(node) = node else {
+                computed.push((0, 0));
+                continue;
+            };
+
+            if !children_done {
+                pending.push((Some(node), true));
+                pending.push((node.right.0.as_deref(), false));
+                pending.push((node.left.0.as_deref(), false));
+                continue;
+            }
+
+            let (right_height, right_size) = computed.pop().unwrap();
+            let (left_height, left_size) = computed.pop().unwrap();
+            let height = 1 + max(left_height, right_height);
+            let size = 1 + left_size + right_size;
+
+            report.node_count += 1;
+            if node.height != height {
+                report.stale_heights += 1;
+            }
+            if node.size != size {
+                report.stale_sizes += 1;
+            }
+            if balanced && left_height.abs_diff(right_height) > 1 {
+                report.unbalanced_nodes += 1;
+            }
+            computed.push((height, size));
+        }
+
+        report.height = computed.pop().map_or(0, |(height, _)| height);
+        report
+    }
+
+    /// Returns `true` if no invariant of the tree is broken
+    pub fn is_valid(&self) -> bool {
+        self.node_count == self.recorded_size
+            && self.order_violations == 0
+            && self.stale_heights == 0
+            && self.stale_sizes == 0
+            && self.unbalanced_nodes == 0
+    }
+}
@@ -0,0 +1,116 @@
+use super::{
+    node::Node,
+    tree::{Path, Side, Tree},
+};
+use std::mem;
+
+/// A tree held apart along the path from its root to one of its links: the
+/// nodes on the path are detached from the child they lead to, and the
+/// subtree below, the focus, is held on its own. Reading or changing the
+/// focus and moving it up or down take O(1) time, so whatever found the focus
+/// does not have to search for it again. Dropping the zipper puts the tree back
+/// together as it was; `finish` and `place` do so after the focus changed.
+pub struct Zipper<'a, T> {
+    // `None` once the tree has been put back together
+    root: Option<&'a mut Tree<T>>,
+    path: Path<T>,
+    focus: Tree<T>,
+}
+
+impl<'a, T> Zipper<'a, T> {
+    /// Focuses on the whole tree
+    pub fn new(root: &'a mut Tree<T>) -> Self {
+        let focus = root.take();
+        Zipper {
+            root: Some(root),
+            path: Path::new(),
+            focus,
+        }
+    }
+
+    /// The value at the root of the focus
+    pub fn value(&self) -> Option<&T> {
+        self.focus.0.as_ref().map(|node| &node.value)
+    }
+
+    pub fn value_mut(&mut self) -> Option<&mut T> {
+        self.focus.0.as_mut().map(|node| &mut node.value)
+    }
+
+    pub fn focus(&self) -> &Tree<T> {
+        &self.focus
+    }
+
+    /// The nodes above the focus, from the root down
+    pub fn path(&self) -> &Path<T> {
+        &self.path
+    }
+
+    /// The focused subtree, which may be changed in any way that keeps the
+    /// tree ordered as long as the zipper is then put back with `finish`
+    pub fn focus_mut(&mut self) -> &mut Tree<T> {
+        &mut self.focus
+    }
+
+    /// Moves the focus to a child of its root, which must not be empty
+    pub fn descend(&mut self, side: Side) {
+        let node = self.focus.0.take().unwrap();
+        self.focus = self.path.descend(node, side);
+    }
+
+    /// Moves the focus up to its parent and returns the side of the parent
+    /// it was on, or `None` if the focus is already the whole tree. Nodes are
+    /// not fixed on the way, so the focus must not have changed shape since
+    /// it was last moved down.
+    pub fn ascend(&mut self) -> Option<Side> {
+        let (mut node, side) = self.path.0.pop()?;
+        match side {
+            Side::Left => node.left = self.focus.take(),
+            Side::Right => node.right = self.focus.take(),
+        }
+        self.focus = Tree(Some(node));
+        Some(side)
+    }
+
+    /// Moves the focus back to the whole tree, fixing every node above it,
+    /// and rebalancing them if `balance` is set
+    pub fn rewind(&mut self, balance: bool) {
+        let path = mem::replace(&mut self.path, Path::new());
+        self.focus = path.rebuild(self.focus.take(), balance);
+    }
+
+    /// Puts the tree back together, fixing every node above the focus, and
+    /// rebalancing them if `balance` is set
+    pub fn finish(mut self, balance: bool) {
+        let root = self.root.take().unwrap();
+        let path = mem::replace(&mut self.path, Path::new());
+        *root = path.rebuild(self.focus.take(), balance);
+    }
+
+    /// Puts `node`, which must have no children, into the empty focus and
+    /// returns its value
+    pub fn place(mut self, node: Box<Node<T>>, balance: bool) -> &'a mut T {
+        let root = self.root.take().unwrap();
+        let path = mem::replace(&mut self.path, Path::new());
+        path.place(root, node, balance)
+    }
+
+    /// Puts the unchanged tree back together and returns the link to the
+    /// focused subtree
+    pub fn into_focus(mut self) -> &'a mut Tree<T> {
+        let root = self.root.take().unwrap();
+        let path = mem::replace(&mut self.path, Path::new());
+        let link = path.relink(root, 0);
+        *link = self.focus.take();
+        link
+    }
+}
+
+impl<T> Drop for Zipper<'_, T> {
+    fn drop(&mut self) {
+        if let Some(root) = self.root.take() {
+            let path = mem::replace(&mut self.path, Path::new());
+            *path.relink(root, 0) = self.focus.take();
+        }
+    }
+}
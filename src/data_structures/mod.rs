@@ -1,2 +1,6 @@
 pub mod binary_search_tree;
+pub mod red_black_tree;
 pub mod singly_linked_list;
+pub mod splay_tree;
+mod traversal;
+pub mod treap;
@@ -0,0 +1,11 @@
+#[allow(clippy::module_inception)]
+mod red_black_tree;
+pub use red_black_tree::RedBlackTree;
+
+mod node;
+#[cfg(feature = "serde")]
+mod serde;
+mod tree;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,55 @@
+use super::tree::Tree;
+use crate::data_structures::traversal::BinaryNode;
+use std::ops::Not;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Black,
+}
+
+impl Not for Color {
+    type Output = Color;
+
+    fn not(self) -> Color {
+        match self {
+            Color::Red => Color::Black,
+            Color::Black => Color::Red,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Node<T: Ord> {
+    pub value: T,
+    pub color: Color,
+    pub left: Tree<T>,
+    pub right: Tree<T>,
+}
+
+impl<T: Ord> Node<T> {
+    pub fn new(value: T) -> Self {
+        Node {
+            value,
+            color: Color::Red,
+            left: Tree(None),
+            right: Tree(None),
+        }
+    }
+}
+
+impl<T: Ord> BinaryNode for Node<T> {
+    type Value = T;
+
+    fn value(&self) -> &T {
+        &self.value
+    }
+
+    fn left(&self) -> Option<&Self> {
+        self.left.0.as_deref()
+    }
+
+    fn right(&self) -> Option<&Self> {
+        self.right.0.as_deref()
+    }
+}
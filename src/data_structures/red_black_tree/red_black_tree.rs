@@ -0,0 +1,183 @@
+use super::{node::Node, tree::Tree};
+use crate::data_structures::traversal::{
+    InorderTraversal, LevelOrderTraversal, PostorderTraversal, PreorderTraversal,
+    ReverseOrderTraversal,
+};
+use std::{
+    cmp::PartialEq,
+    fmt,
+    iter::{Extend, FromIterator},
+};
+
+/// An ordered set backed by a left-leaning red-black tree. It offers the same
+/// operations as `BinarySearchTree`, but guarantees O(log n) height in the
+/// worst case while doing fewer rotations per write than an AVL tree.
+#[derive(Debug)]
+pub struct RedBlackTree<T: Ord> {
+    pub(super) root: Tree<T>,
+    pub size: usize,
+}
+
+impl<T: Ord> PartialEq for RedBlackTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.inorder().eq(other.inorder())
+    }
+}
+
+impl<T: Ord + fmt::Debug> fmt::Display for RedBlackTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.sorted_vec())
+    }
+}
+
+impl<T: Ord> Extend<T> for RedBlackTree<T> {
+    /// Extends RedBlackTree elements from iterators
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::red_black_tree::RedBlackTree;
+    /// use std::iter::Extend;
+    ///
+    /// let mut tree: RedBlackTree<i32> = RedBlackTree::new();
+    /// tree.extend(vec![7, 1, 0, 4, 5, 3].into_iter());
+    /// assert_eq!(tree.sorted_vec(), [&0, &1, &3, &4, &5, &7]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |element| {
+            self.insert(element);
+        });
+    }
+}
+
+impl<T: Ord> FromIterator<T> for RedBlackTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = RedBlackTree::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T: Ord> Default for RedBlackTree<T> {
+    fn default() -> Self {
+        RedBlackTree::new()
+    }
+}
+
+impl<T: Ord> RedBlackTree<T> {
+    pub fn new() -> Self {
+        RedBlackTree {
+            root: Tree(None),
+            size: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn clear(&mut self) {
+        *self = RedBlackTree::new();
+    }
+
+    pub fn root(&self) -> Option<&T> {
+        self.root.0.as_ref().map(|node| &node.value)
+    }
+
+    /// Number of nodes on the longest path from the root to a leaf, at most
+    /// 2 * log2(n + 1)
+    pub fn height(&self) -> usize {
+        self.root.height()
+    }
+
+    pub fn insert(&mut self, value: T) -> bool {
+        self.size += 1;
+        self.root.insert(value, true)
+    }
+
+    pub fn insert_without_dup(&mut self, value: T) -> bool {
+        let res = self.root.insert(value, false);
+        if !res {
+            self.size += 1;
+        }
+        res
+    }
+
+    pub fn contains(&self, target: &T) -> bool {
+        self.root.contains(target)
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.root.min()
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        self.root.max()
+    }
+
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        self.root.successor(value)
+    }
+
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        self.root.predecessor(value)
+    }
+
+    pub fn extract_min(&mut self) -> Option<T> {
+        let res = self.root.extract_min();
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn extract_max(&mut self) -> Option<T> {
+        let res = self.root.extract_max();
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn remove(&mut self, target: &T) -> bool {
+        let res = self.root.remove(target);
+        if res {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn sorted_vec(&self) -> Vec<&T> {
+        self.inorder().collect()
+    }
+
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut elements = Vec::with_capacity(self.size);
+        while let Some(element) = self.root.extract_min() {
+            elements.push(element);
+        }
+        elements
+    }
+
+    pub fn inorder(&self) -> InorderTraversal<'_, Node<T>> {
+        InorderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn reverse_order(&self) -> ReverseOrderTraversal<'_, Node<T>> {
+        ReverseOrderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn preorder(&self) -> PreorderTraversal<'_, Node<T>> {
+        PreorderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn postorder(&self) -> PostorderTraversal<'_, Node<T>> {
+        PostorderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn level_order(&self) -> LevelOrderTraversal<'_, Node<T>> {
+        LevelOrderTraversal::new(self.root.0.as_deref())
+    }
+}
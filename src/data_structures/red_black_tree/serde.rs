@@ -0,0 +1,27 @@
+//! `Serialize` and `Deserialize` for `RedBlackTree`, enabled by the `serde`
+//! feature. Trees are written as a sequence of their elements in sorted
+//! order, like a `BinarySearchTree`.
+
+use super::red_black_tree::RedBlackTree;
+use serde::{
+    de::{Deserialize, Deserializer},
+    ser::{Serialize, SerializeSeq, Serializer},
+};
+
+impl<T: Ord + Serialize> Serialize for RedBlackTree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self.inorder() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T: Ord + Deserialize<'de>> Deserialize<'de> for RedBlackTree<T> {
+    /// Inserts the elements of a sequence in any order, keeping duplicates.
+    /// The tree balances itself as they are inserted.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
@@ -0,0 +1,244 @@
+use super::{node::Color, tree::Tree};
+use crate::data_structures::{binary_search_tree::BinarySearchTree, red_black_tree::RedBlackTree};
+
+/// Largest height allowed for a red-black tree holding `size` elements
+fn height_bound(size: usize) -> usize {
+    2 * ((size + 1) as f64).log2().ceil() as usize
+}
+
+fn is_red<T: Ord>(tree: &Tree<T>) -> bool {
+    tree.0.as_ref().is_some_and(|node| node.color == Color::Red)
+}
+
+/// Checks the left-leaning red-black invariants below `tree`, returning the
+/// number of black links on every path from it to an empty link
+fn black_height<T: Ord>(tree: &Tree<T>) -> usize {
+    let Some(node) = tree.0.as_ref() else {
+        return 0;
+    };
+    assert!(!is_red(&node.right), "red right link");
+    assert!(
+        node.color == Color::Black || !is_red(&node.left),
+        "two red links in a row"
+    );
+
+    let left = black_height(&node.left);
+    assert_eq!(left, black_height(&node.right), "unequal black height");
+    left + usize::from(node.color == Color::Black)
+}
+
+/// Checks that `tree` is ordered and a valid left-leaning red-black tree
+fn assert_red_black<T: Ord>(tree: &RedBlackTree<T>) {
+    assert!(!is_red(&tree.root), "red root");
+    black_height(&tree.root);
+    assert!(tree.inorder().is_sorted());
+    assert_eq!(tree.inorder().count(), tree.len());
+}
+
+#[test]
+fn basics() {
+    let mut tree: RedBlackTree<i32> = RedBlackTree::new();
+
+    // Check empty RedBlackTree behaves correctly
+    assert!(tree.is_empty());
+    assert_eq!(tree.size, 0);
+    assert_eq!(tree.height(), 0);
+    assert_eq!(tree.min(), None);
+    assert_eq!(tree.max(), None);
+    assert!(!tree.contains(&5));
+    assert!(!tree.remove(&5));
+
+    assert!(!tree.insert(1));
+    assert!(!tree.insert(0));
+    assert!(!tree.insert(-2));
+    assert!(!tree.insert(5));
+    assert!(tree.insert(5)); // duplicate
+    assert!(!tree.insert(15));
+    assert!(!tree.insert(-5));
+    assert!(!tree.insert(3));
+    assert!(tree.insert(0)); // duplicate
+    assert_red_black(&tree);
+
+    assert_eq!(tree.size, 9);
+    assert_eq!(
+        tree.sorted_vec(),
+        vec![&-5, &-2, &0, &0, &1, &3, &5, &5, &15]
+    );
+    assert_eq!(tree.min(), Some(&-5));
+    assert_eq!(tree.max(), Some(&15));
+    assert!(tree.contains(&3));
+    assert!(!tree.contains(&55));
+
+    assert!(!tree.remove(&100));
+    assert!(tree.remove(&0));
+    assert!(tree.remove(&5));
+    assert_eq!(tree.sorted_vec(), vec![&-5, &-2, &0, &1, &3, &5, &15]);
+    assert_red_black(&tree);
+    assert!(tree.remove(&1));
+    assert!(tree.remove(&-5));
+    assert!(tree.remove(&15));
+    assert_eq!(tree.sorted_vec(), vec![&-2, &0, &3, &5]);
+    assert!(tree.remove(&0));
+    assert!(tree.remove(&-2));
+    assert!(tree.remove(&5));
+    assert!(tree.remove(&3));
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), None);
+}
+
+#[test]
+fn insert_without_duplication() {
+    let mut tree: RedBlackTree<i32> = RedBlackTree::new();
+    assert!(!tree.insert_without_dup(0));
+    assert!(!tree.insert_without_dup(1));
+    assert!(tree.insert_without_dup(0));
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree.sorted_vec(), vec![&0, &1]);
+}
+
+#[test]
+fn successor_and_predecessor() {
+    let tree: RedBlackTree<i32> = vec![25, 15, 40, 10, 18, 45, 35, 10, 25]
+        .into_iter()
+        .collect();
+
+    assert_eq!(tree.successor(&25), Some(&35));
+    assert_eq!(tree.successor(&18), Some(&25));
+    assert_eq!(tree.successor(&10), Some(&15));
+    assert_eq!(tree.successor(&45), None);
+
+    assert_eq!(tree.predecessor(&25), Some(&18));
+    assert_eq!(tree.predecessor(&40), Some(&35));
+    assert_eq!(tree.predecessor(&15), Some(&10));
+    assert_eq!(tree.predecessor(&10), None);
+}
+
+#[test]
+fn extract_min_and_max() {
+    let mut tree: RedBlackTree<i32> = RedBlackTree::new();
+    assert_eq!(tree.extract_min(), None);
+    assert_eq!(tree.extract_max(), None);
+
+    tree.extend(1..=100);
+    for i in 1..=25 {
+        assert_eq!(tree.extract_min(), Some(i));
+        assert_eq!(tree.extract_max(), Some(101 - i));
+        assert!(tree.height() <= height_bound(tree.len()));
+        assert_red_black(&tree);
+    }
+    assert_eq!(tree.len(), 50);
+    assert_eq!(tree.into_sorted_vec(), (26..=75).collect::<Vec<i32>>());
+}
+
+#[test]
+fn traversals() {
+    let tree: RedBlackTree<i32> = (1..=7).collect();
+    assert_eq!(tree.root(), Some(&4));
+    assert_eq!(
+        tree.inorder().collect::<Vec<&i32>>(),
+        vec![&1, &2, &3, &4, &5, &6, &7]
+    );
+    assert_eq!(
+        tree.reverse_order().collect::<Vec<&i32>>(),
+        vec![&7, &6, &5, &4, &3, &2, &1]
+    );
+    assert_eq!(
+        tree.preorder().collect::<Vec<&i32>>(),
+        vec![&4, &2, &1, &3, &6, &5, &7]
+    );
+    assert_eq!(
+        tree.postorder().collect::<Vec<&i32>>(),
+        vec![&1, &3, &2, &5, &7, &6, &4]
+    );
+    assert_eq!(
+        tree.level_order().collect::<Vec<&i32>>(),
+        vec![&4, &2, &6, &1, &3, &5, &7]
+    );
+}
+
+#[test]
+fn sorted_inserts_stay_balanced() {
+    let mut tree: RedBlackTree<i32> = RedBlackTree::new();
+    for i in 0..10_000 {
+        tree.insert(i);
+    }
+    assert!(tree.height() <= height_bound(tree.len()));
+    assert_red_black(&tree);
+
+    for i in (0..10_000).step_by(2) {
+        assert!(tree.remove(&i));
+        if i % 500 == 0 {
+            assert_red_black(&tree);
+        }
+    }
+    assert_eq!(tree.len(), 5_000);
+    assert!(tree.height() <= height_bound(tree.len()));
+    assert_red_black(&tree);
+    assert_eq!(tree.min(), Some(&1));
+    assert_eq!(tree.max(), Some(&9_999));
+}
+
+#[test]
+fn matches_binary_search_tree() {
+    let mut red_black: RedBlackTree<i32> = RedBlackTree::new();
+    let mut unbalanced: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+
+    for i in 0..2_000 {
+        let value = (i * 37) % 311;
+        assert_eq!(red_black.insert(value), unbalanced.insert(value));
+        assert_eq!(
+            red_black.insert_without_dup(value + 1),
+            unbalanced.insert_without_dup(value + 1)
+        );
+        assert_red_black(&red_black);
+    }
+    for i in 0..1_500 {
+        let value = (i * 53) % 317;
+        assert_eq!(red_black.remove(&value), unbalanced.remove(&value));
+        assert_eq!(red_black.successor(&value), unbalanced.successor(&value));
+        assert_eq!(
+            red_black.predecessor(&value),
+            unbalanced.predecessor(&value)
+        );
+        assert_red_black(&red_black);
+    }
+    for _ in 0..100 {
+        assert_eq!(red_black.extract_min(), unbalanced.extract_min());
+        assert_eq!(red_black.extract_max(), unbalanced.extract_max());
+        assert_red_black(&red_black);
+    }
+
+    assert_eq!(red_black.len(), unbalanced.len());
+    assert_eq!(red_black.sorted_vec(), unbalanced.sorted_vec());
+    assert!(red_black.height() <= height_bound(red_black.len()));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    let tree: RedBlackTree<i32> = vec![5, 1, 3, 3].into_iter().collect();
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(json, "[1,3,3,5]");
+
+    let loaded: RedBlackTree<i32> = serde_json::from_str("[9,2,7,2]").unwrap();
+    assert_eq!(loaded.sorted_vec(), [&2, &2, &7, &9]);
+    assert_eq!(
+        serde_json::from_str::<RedBlackTree<i32>>(&json).unwrap(),
+        tree
+    );
+}
+
+#[test]
+fn removes_duplicates() {
+    let mut tree: RedBlackTree<i32> = RedBlackTree::new();
+    for value in [3, 2, 2, 0] {
+        tree.insert(value);
+    }
+
+    assert!(tree.remove(&2));
+    assert_red_black(&tree);
+    assert!(tree.remove(&2));
+    assert_red_black(&tree);
+    assert!(!tree.remove(&2));
+    assert_eq!(tree.inorder().collect::<Vec<_>>(), vec![&0, &3]);
+}
@@ -0,0 +1,320 @@
+use super::node::{Color, Node};
+use std::{
+    cmp::{Ordering, max},
+    mem,
+};
+
+/// A left-leaning red-black tree: red links always lean left and no node has
+/// two red links, which keeps every path from the root within twice the
+/// length of the shortest one.
+#[derive(Debug)]
+pub struct Tree<T: Ord>(pub Option<Box<Node<T>>>);
+
+impl<T: Ord> Tree<T> {
+    pub fn take(&mut self) -> Tree<T> {
+        Tree(self.0.take())
+    }
+
+    fn node(&self) -> &Node<T> {
+        self.0.as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self) -> &mut Node<T> {
+        self.0.as_mut().unwrap()
+    }
+
+    fn is_red(&self) -> bool {
+        self.0.as_ref().is_some_and(|node| node.color == Color::Red)
+    }
+
+    fn left_is_red(&self) -> bool {
+        self.0.as_ref().is_some_and(|node| node.left.is_red())
+    }
+
+    fn set_color(&mut self, color: Color) {
+        if let Some(node) = self.0.as_mut() {
+            node.color = color;
+        }
+    }
+
+    fn rotate_left(&mut self) {
+        let mut root = self.0.take().unwrap();
+        let mut pivot = root.right.0.take().unwrap();
+        root.right = pivot.left.take();
+        pivot.color = root.color;
+        root.color = Color::Red;
+        pivot.left = Tree(Some(root));
+        self.0 = Some(pivot);
+    }
+
+    fn rotate_right(&mut self) {
+        let mut root = self.0.take().unwrap();
+        let mut pivot = root.left.0.take().unwrap();
+        root.left = pivot.right.take();
+        pivot.color = root.color;
+        root.color = Color::Red;
+        pivot.right = Tree(Some(root));
+        self.0 = Some(pivot);
+    }
+
+    fn flip_colors(&mut self) {
+        let node = self.node_mut();
+        node.color = !node.color;
+        for child in [&mut node.left, &mut node.right] {
+            if let Some(child) = child.0.as_mut() {
+                child.color = !child.color;
+            }
+        }
+    }
+
+    /// Restores the left-leaning invariants at the root of this subtree on
+    /// the way back up from an insertion or removal
+    fn balance(&mut self) {
+        if self.node().right.is_red() && !self.node().left.is_red() {
+            self.rotate_left();
+        }
+        if self.node().left.is_red() && self.node().left.left_is_red() {
+            self.rotate_right();
+        }
+        if self.node().left.is_red() && self.node().right.is_red() {
+            self.flip_colors();
+        }
+    }
+
+    /// Makes the left child or one of its children red before descending
+    /// left to remove a node
+    fn move_red_left(&mut self) {
+        self.flip_colors();
+        if self.node().right.left_is_red() {
+            self.node_mut().right.rotate_right();
+            self.rotate_left();
+            self.flip_colors();
+        }
+    }
+
+    /// Makes the right child or one of its children red before descending
+    /// right to remove a node, returning whether the old root was rotated
+    /// down into the right subtree
+    fn move_red_right(&mut self) -> bool {
+        self.flip_colors();
+        if self.node().left.left_is_red() {
+            self.rotate_right();
+            self.flip_colors();
+            return true;
+        }
+        false
+    }
+
+    pub fn insert(&mut self, value: T, allow_duplicate: bool) -> bool {
+        let is_duplicate = self.insert_node(value, allow_duplicate);
+        self.set_color(Color::Black);
+        is_duplicate
+    }
+
+    fn insert_node(&mut self, value: T, allow_duplicate: bool) -> bool {
+        let Some(node) = self.0.as_mut() else {
+            self.0 = Some(Box::new(Node::new(value)));
+            return false;
+        };
+
+        let is_duplicate = match node.value.cmp(&value) {
+            Ordering::Greater => node.left.insert_node(value, allow_duplicate),
+            Ordering::Less => node.right.insert_node(value, allow_duplicate),
+            Ordering::Equal => {
+                if !allow_duplicate {
+                    return true;
+                }
+                node.right.insert_node(value, allow_duplicate);
+                true
+            }
+        };
+
+        self.balance();
+        is_duplicate
+    }
+
+    pub fn contains(&self, target: &T) -> bool {
+        let mut current = self;
+
+        while let Some(ref node) = current.0 {
+            match node.value.cmp(target) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Less => current = &node.right,
+                Ordering::Equal => return true,
+            }
+        }
+
+        false
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        let mut current = self.0.as_ref()?;
+
+        while let Some(left) = current.left.0.as_ref() {
+            current = left;
+        }
+
+        Some(&current.value)
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        let mut current = self.0.as_ref()?;
+
+        while let Some(right) = current.right.0.as_ref() {
+            current = right;
+        }
+
+        Some(&current.value)
+    }
+
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        let mut current = self.0.as_ref();
+        let mut successor = None;
+
+        while let Some(node) = current {
+            if node.value > *value {
+                successor = current;
+                current = node.left.0.as_ref();
+            } else {
+                current = node.right.0.as_ref();
+            }
+        }
+
+        successor.map(|node| &node.value)
+    }
+
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        let mut current = self.0.as_ref();
+        let mut predecessor = None;
+
+        while let Some(node) = current {
+            if node.value < *value {
+                predecessor = current;
+                current = node.right.0.as_ref();
+            } else {
+                current = node.left.0.as_ref();
+            }
+        }
+
+        predecessor.map(|node| &node.value)
+    }
+
+    pub fn extract_min(&mut self) -> Option<T> {
+        self.0.as_ref()?;
+
+        if !self.node().left.is_red() && !self.node().right.is_red() {
+            self.set_color(Color::Red);
+        }
+        let value = self.remove_min();
+        self.set_color(Color::Black);
+
+        Some(value)
+    }
+
+    fn remove_min(&mut self) -> T {
+        if self.node().left.0.is_none() {
+            let node = *self.0.take().unwrap();
+            *self = node.right;
+            return node.value;
+        }
+
+        if !self.node().left.is_red() && !self.node().left.left_is_red() {
+            self.move_red_left();
+        }
+        let value = self.node_mut().left.remove_min();
+        self.balance();
+
+        value
+    }
+
+    pub fn extract_max(&mut self) -> Option<T> {
+        self.0.as_ref()?;
+
+        if !self.node().left.is_red() && !self.node().right.is_red() {
+            self.set_color(Color::Red);
+        }
+        let value = self.remove_max();
+        self.set_color(Color::Black);
+
+        Some(value)
+    }
+
+    fn remove_max(&mut self) -> T {
+        if self.node().left.is_red() {
+            self.rotate_right();
+        }
+
+        if self.node().right.0.is_none() {
+            let node = *self.0.take().unwrap();
+            *self = node.left;
+            return node.value;
+        }
+
+        if !self.node().right.is_red() && !self.node().right.left_is_red() {
+            self.move_red_right();
+        }
+        let value = self.node_mut().right.remove_max();
+        self.balance();
+
+        value
+    }
+
+    pub fn remove(&mut self, target: &T) -> bool {
+        if !self.contains(target) {
+            return false; // an element with 'target' value was not found
+        }
+
+        if !self.node().left.is_red() && !self.node().right.is_red() {
+            self.set_color(Color::Red);
+        }
+        self.remove_node(target);
+        self.set_color(Color::Black);
+
+        true
+    }
+
+    /// Removes one node equal to `target`, which must be in this subtree
+    fn remove_node(&mut self, target: &T) -> T {
+        let value = if self.node().value > *target {
+            if !self.node().left.is_red() && !self.node().left.left_is_red() {
+                self.move_red_left();
+            }
+            self.node_mut().left.remove_node(target)
+        } else {
+            if self.node().left.is_red() {
+                self.rotate_right();
+            }
+
+            if self.node().value == *target && self.node().right.0.is_none() {
+                let node = *self.0.take().unwrap();
+                *self = node.left;
+                return node.value;
+            }
+
+            // After a rotation the old root, which is not above `target`, sits
+            // in the right subtree. The new root may be a duplicate of
+            // `target`, but the right subtree leans right until it is
+            // rebalanced, so the removal continues there instead.
+            let rotated = !self.node().right.is_red()
+                && !self.node().right.left_is_red()
+                && self.move_red_right();
+
+            if !rotated && self.node().value == *target {
+                // Replace value of current node with its successor
+                let successor = self.node_mut().right.remove_min();
+                mem::replace(&mut self.node_mut().value, successor)
+            } else {
+                self.node_mut().right.remove_node(target)
+            }
+        };
+
+        self.balance();
+        value
+    }
+
+    pub fn height(&self) -> usize {
+        self.0
+            .as_ref()
+            .map_or(0, |node| 1 + max(node.left.height(), node.right.height()))
+    }
+}
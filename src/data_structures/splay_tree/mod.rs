@@ -0,0 +1,11 @@
+#[allow(clippy::module_inception)]
+mod splay_tree;
+pub use splay_tree::SplayTree;
+
+mod node;
+#[cfg(feature = "serde")]
+mod serde;
+mod tree;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,34 @@
+use super::tree::Tree;
+use crate::data_structures::traversal::BinaryNode;
+
+pub struct Node<T: Ord> {
+    pub value: T,
+    pub left: Tree<T>,
+    pub right: Tree<T>,
+}
+
+impl<T: Ord> Node<T> {
+    pub fn new(value: T) -> Self {
+        Node {
+            value,
+            left: Tree(None),
+            right: Tree(None),
+        }
+    }
+}
+
+impl<T: Ord> BinaryNode for Node<T> {
+    type Value = T;
+
+    fn value(&self) -> &T {
+        &self.value
+    }
+
+    fn left(&self) -> Option<&Self> {
+        self.left.0.as_deref()
+    }
+
+    fn right(&self) -> Option<&Self> {
+        self.right.0.as_deref()
+    }
+}
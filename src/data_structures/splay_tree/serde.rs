@@ -0,0 +1,27 @@
+//! `Serialize` and `Deserialize` for `SplayTree`, enabled by the `serde`
+//! feature. Trees are written as a sequence of their elements in sorted
+//! order, like a `BinarySearchTree`, so the shape left by earlier accesses
+//! is not kept.
+
+use super::splay_tree::SplayTree;
+use serde::{
+    de::{Deserialize, Deserializer},
+    ser::{Serialize, SerializeSeq, Serializer},
+};
+
+impl<T: Ord + Serialize> Serialize for SplayTree<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self.inorder() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T: Ord + Deserialize<'de>> Deserialize<'de> for SplayTree<T> {
+    /// Inserts the elements of a sequence in any order, keeping duplicates
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
@@ -0,0 +1,232 @@
+use super::{node::Node, tree::Tree};
+use crate::data_structures::traversal::{
+    InorderTraversal, LevelOrderTraversal, PostorderTraversal, PreorderTraversal,
+    ReverseOrderTraversal,
+};
+use std::{
+    cmp::PartialEq,
+    fmt,
+    iter::{Extend, FromIterator},
+};
+
+/// An ordered set backed by a splay tree, which moves every element it looks
+/// up, inserts or removes to the root. It keeps no balance information and
+/// a single operation can take O(n) time, but any sequence of m operations
+/// takes O((m + n) log n), and elements that were accessed recently are near
+/// the top, so lookups that keep returning to a few hot elements are cheap.
+/// Because lookups reshape the tree, `contains` and `get` take `&mut self`.
+pub struct SplayTree<T: Ord> {
+    pub(super) root: Tree<T>,
+    pub size: usize,
+}
+
+impl<T: Ord + fmt::Debug> fmt::Debug for SplayTree<T> {
+    /// Lists the elements in sorted order. Walking the nodes iteratively
+    /// keeps formatting a degenerate tree from overflowing the stack.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.inorder()).finish()
+    }
+}
+
+impl<T: Ord> PartialEq for SplayTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.inorder().eq(other.inorder())
+    }
+}
+
+impl<T: Ord + fmt::Debug> fmt::Display for SplayTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.sorted_vec())
+    }
+}
+
+impl<T: Ord> Extend<T> for SplayTree<T> {
+    /// Extends SplayTree elements from iterators
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::splay_tree::SplayTree;
+    /// use std::iter::Extend;
+    ///
+    /// let mut tree: SplayTree<i32> = SplayTree::new();
+    /// tree.extend(vec![7, 1, 0, 4, 5, 3].into_iter());
+    /// assert_eq!(tree.sorted_vec(), [&0, &1, &3, &4, &5, &7]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |element| {
+            self.insert(element);
+        });
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SplayTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = SplayTree::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T: Ord> Default for SplayTree<T> {
+    fn default() -> Self {
+        SplayTree::new()
+    }
+}
+
+impl<T: Ord> SplayTree<T> {
+    pub fn new() -> Self {
+        SplayTree {
+            root: Tree(None),
+            size: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn clear(&mut self) {
+        *self = SplayTree::new();
+    }
+
+    /// The element at the root, which is the one accessed last
+    pub fn root(&self) -> Option<&T> {
+        self.root.0.as_ref().map(|node| &node.value)
+    }
+
+    pub fn height(&self) -> usize {
+        self.root.height()
+    }
+
+    /// Number of edges between the root and an element equal to `target`,
+    /// which is what looking it up would cost. Unlike `contains`, this does
+    /// not splay it.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::splay_tree::SplayTree;
+    ///
+    /// let mut tree: SplayTree<i32> = (0..100).collect();
+    /// assert_eq!(tree.depth_of(&0), Some(99));
+    /// assert!(tree.contains(&0));
+    /// assert_eq!(tree.depth_of(&0), Some(0));
+    /// assert_eq!(tree.depth_of(&100), None);
+    /// ```
+    pub fn depth_of(&self, target: &T) -> Option<usize> {
+        self.root.depth_of(target)
+    }
+
+    pub fn insert(&mut self, value: T) -> bool {
+        self.size += 1;
+        self.root.insert(value, true)
+    }
+
+    pub fn insert_without_dup(&mut self, value: T) -> bool {
+        let res = self.root.insert(value, false);
+        if !res {
+            self.size += 1;
+        }
+        res
+    }
+
+    /// Returns `true` if the tree holds an element equal to `target`, moving
+    /// it to the root. If there is none, the last element on the search path
+    /// is moved there instead.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::splay_tree::SplayTree;
+    ///
+    /// let mut tree: SplayTree<i32> = vec![3, 1, 2].into_iter().collect();
+    /// assert!(tree.contains(&3));
+    /// assert_eq!(tree.root(), Some(&3));
+    /// assert!(!tree.contains(&0));
+    /// assert_eq!(tree.root(), Some(&1));
+    /// ```
+    pub fn contains(&mut self, target: &T) -> bool {
+        self.root.splay(target)
+    }
+
+    /// Returns the stored element equal to `target`, after moving it to the
+    /// root
+    pub fn get(&mut self, target: &T) -> Option<&T> {
+        if self.root.splay(target) {
+            self.root()
+        } else {
+            None
+        }
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.root.min()
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        self.root.max()
+    }
+
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        self.root.successor(value)
+    }
+
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        self.root.predecessor(value)
+    }
+
+    pub fn extract_min(&mut self) -> Option<T> {
+        let res = self.root.extract_min();
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn extract_max(&mut self) -> Option<T> {
+        let res = self.root.extract_max();
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn remove(&mut self, target: &T) -> bool {
+        let res = self.root.remove(target);
+        if res {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn sorted_vec(&self) -> Vec<&T> {
+        self.inorder().collect()
+    }
+
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut elements = Vec::with_capacity(self.size);
+        while let Some(element) = self.root.extract_min() {
+            elements.push(element);
+        }
+        elements
+    }
+
+    pub fn inorder(&self) -> InorderTraversal<'_, Node<T>> {
+        InorderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn reverse_order(&self) -> ReverseOrderTraversal<'_, Node<T>> {
+        ReverseOrderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn preorder(&self) -> PreorderTraversal<'_, Node<T>> {
+        PreorderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn postorder(&self) -> PostorderTraversal<'_, Node<T>> {
+        PostorderTraversal::new(self.root.0.as_deref())
+    }
+
+    pub fn level_order(&self) -> LevelOrderTraversal<'_, Node<T>> {
+        LevelOrderTraversal::new(self.root.0.as_deref())
+    }
+}
@@ -0,0 +1,231 @@
+use crate::data_structures::{binary_search_tree::BinarySearchTree, splay_tree::SplayTree};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+/// `size` distinct keys in an order that is random but the same on every run
+fn shuffled_keys(size: i32, seed: u64) -> Vec<i32> {
+    let mut keys: Vec<i32> = (0..size).collect();
+    keys.shuffle(&mut StdRng::seed_from_u64(seed));
+    keys
+}
+
+#[test]
+fn basics() {
+    let mut tree: SplayTree<i32> = SplayTree::new();
+
+    // Check empty SplayTree behaves correctly
+    assert!(tree.is_empty());
+    assert_eq!(tree.size, 0);
+    assert_eq!(tree.height(), 0);
+    assert_eq!(tree.min(), None);
+    assert_eq!(tree.max(), None);
+    assert!(!tree.contains(&5));
+    assert_eq!(tree.get(&5), None);
+    assert!(!tree.remove(&5));
+
+    assert!(!tree.insert(1));
+    assert!(!tree.insert(0));
+    assert!(!tree.insert(-2));
+    assert!(!tree.insert(5));
+    assert!(tree.insert(5)); // duplicate
+    assert!(!tree.insert(15));
+    assert!(!tree.insert(-5));
+    assert!(!tree.insert(3));
+    assert!(tree.insert(0)); // duplicate
+    assert_eq!(tree.root(), Some(&0));
+
+    assert_eq!(tree.size, 9);
+    assert_eq!(
+        tree.sorted_vec(),
+        vec![&-5, &-2, &0, &0, &1, &3, &5, &5, &15]
+    );
+    assert_eq!(tree.min(), Some(&-5));
+    assert_eq!(tree.max(), Some(&15));
+    assert!(tree.contains(&3));
+    assert_eq!(tree.root(), Some(&3));
+    assert!(!tree.contains(&55));
+    assert_eq!(tree.get(&15), Some(&15));
+    assert_eq!(tree.root(), Some(&15));
+    assert_eq!(tree.successor(&0), Some(&1));
+    assert_eq!(tree.predecessor(&0), Some(&-2));
+
+    assert!(!tree.remove(&100));
+    assert!(tree.remove(&0));
+    assert!(tree.remove(&5));
+    assert_eq!(tree.sorted_vec(), vec![&-5, &-2, &0, &1, &3, &5, &15]);
+    assert_eq!(tree.extract_min(), Some(-5));
+    assert_eq!(tree.extract_max(), Some(15));
+    assert!(!tree.insert_without_dup(4));
+    assert!(tree.insert_without_dup(4));
+    assert_eq!(tree.len(), 6);
+    assert_eq!(tree.into_sorted_vec(), [-2, 0, 1, 3, 4, 5]);
+
+    let mut tree: SplayTree<i32> = (0..10).collect();
+    tree.clear();
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), None);
+}
+
+#[test]
+fn traversals() {
+    // Each insert splays the new element, so sorted inserts leave a path
+    let mut tree: SplayTree<i32> = (1..=7).collect();
+    assert_eq!(tree.height(), 7);
+    assert_eq!(
+        tree.preorder().collect::<Vec<&i32>>(),
+        vec![&7, &6, &5, &4, &3, &2, &1]
+    );
+
+    // Splaying the deepest element roughly halves the depth of the path
+    assert!(tree.contains(&1));
+    assert_eq!(tree.height(), 5);
+    assert_eq!(
+        tree.inorder().collect::<Vec<&i32>>(),
+        vec![&1, &2, &3, &4, &5, &6, &7]
+    );
+    assert_eq!(
+        tree.reverse_order().collect::<Vec<&i32>>(),
+        vec![&7, &6, &5, &4, &3, &2, &1]
+    );
+    assert_eq!(
+        tree.preorder().collect::<Vec<&i32>>(),
+        vec![&1, &6, &4, &2, &3, &5, &7]
+    );
+    assert_eq!(
+        tree.postorder().collect::<Vec<&i32>>(),
+        vec![&3, &2, &5, &4, &7, &6, &1]
+    );
+    assert_eq!(
+        tree.level_order().collect::<Vec<&i32>>(),
+        vec![&1, &6, &4, &7, &2, &5, &3]
+    );
+}
+
+#[test]
+fn matches_binary_search_tree() {
+    let mut splay: SplayTree<i32> = SplayTree::new();
+    let mut unbalanced: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+
+    for i in 0..2_000 {
+        let value = (i * 37) % 311;
+        assert_eq!(splay.insert(value), unbalanced.insert(value));
+        assert_eq!(
+            splay.insert_without_dup(value + 1),
+            unbalanced.insert_without_dup(value + 1)
+        );
+    }
+    for i in 0..1_500 {
+        let value = (i * 53) % 317;
+        assert_eq!(splay.contains(&value), unbalanced.contains(&value));
+        assert_eq!(splay.remove(&value), unbalanced.remove(&value));
+        assert_eq!(splay.get(&(value + 7)), unbalanced.get(&(value + 7)));
+        assert_eq!(splay.successor(&value), unbalanced.successor(&value));
+        assert_eq!(splay.predecessor(&value), unbalanced.predecessor(&value));
+    }
+    for _ in 0..100 {
+        assert_eq!(splay.extract_min(), unbalanced.extract_min());
+        assert_eq!(splay.extract_max(), unbalanced.extract_max());
+    }
+
+    assert_eq!(splay.len(), unbalanced.len());
+    assert_eq!(splay.sorted_vec(), unbalanced.sorted_vec());
+}
+
+#[test]
+fn sequential_access_takes_linear_time() {
+    // Sorted inserts build a path 100_000 nodes deep, which the walks, the
+    // splaying and dropping the tree must all handle without recursing
+    const SIZE: i32 = 100_000;
+    let mut tree: SplayTree<i32> = (0..SIZE).collect();
+    assert_eq!(tree.height(), SIZE as usize);
+
+    // A single lookup can cost O(n), but reading every element in order
+    // costs O(n) in total rather than O(n log n)
+    let mut cost = 0;
+    for key in 0..SIZE {
+        cost += tree.depth_of(&key).unwrap();
+        assert!(tree.contains(&key));
+    }
+    assert!(cost <= 5 * SIZE as usize, "{cost}");
+}
+
+#[test]
+fn amortized_bound() {
+    // Any m lookups in a tree of n elements cost O((m + n) log n) in total,
+    // however unbalanced the tree starts out
+    const SIZE: i32 = 1_024;
+    const LOOKUPS: usize = 50_000;
+    let log_size = (SIZE as f64).log2() as usize;
+    let mut rng = StdRng::seed_from_u64(11);
+
+    for mut tree in [
+        (0..SIZE).collect::<SplayTree<i32>>(),
+        shuffled_keys(SIZE, 12).into_iter().collect(),
+    ] {
+        let mut cost = 0;
+        for _ in 0..LOOKUPS {
+            let key = rng.random_range(0..SIZE);
+            cost += tree.depth_of(&key).unwrap();
+            assert!(tree.contains(&key));
+        }
+        assert!(cost <= 3 * (LOOKUPS + SIZE as usize) * log_size, "{cost}");
+    }
+}
+
+#[test]
+fn working_set() {
+    // A few hot keys take nearly every lookup. A splay tree keeps them near
+    // the root, while a search tree's cost depends only on where they landed.
+    const SIZE: i32 = 4_096;
+    const LOOKUPS: usize = 20_000;
+    let keys = shuffled_keys(SIZE, 21);
+    let hot = &keys[2_000..2_008];
+    let mut rng = StdRng::seed_from_u64(22);
+    let lookups: Vec<i32> = (0..LOOKUPS)
+        .map(|_| {
+            if rng.random_bool(0.95) {
+                hot[rng.random_range(0..hot.len())]
+            } else {
+                rng.random_range(0..SIZE)
+            }
+        })
+        .collect();
+
+    let mut splay: SplayTree<i32> = keys.iter().copied().collect();
+    let mut unbalanced = BinarySearchTree::new_unbalanced();
+    unbalanced.extend(keys.iter().copied());
+    let balanced: BinarySearchTree<i32> = keys.iter().copied().collect();
+
+    let (mut splay_cost, mut unbalanced_cost, mut balanced_cost) = (0, 0, 0);
+    for key in &lookups {
+        splay_cost += splay.depth_of(key).unwrap();
+        assert!(splay.contains(key));
+        unbalanced_cost += unbalanced.depth_of(key).unwrap();
+        balanced_cost += balanced.depth_of(key).unwrap();
+    }
+
+    assert!(
+        3 * splay_cost < balanced_cost,
+        "{splay_cost} {balanced_cost}"
+    );
+    assert!(
+        3 * splay_cost < unbalanced_cost,
+        "{splay_cost} {unbalanced_cost}"
+    );
+
+    // Once the lookups settle, the hot keys sit in the top few levels
+    assert!(hot.iter().all(|key| splay.depth_of(key).unwrap() < 8));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    let mut tree: SplayTree<i32> = vec![5, 1, 3, 3].into_iter().collect();
+    assert!(tree.contains(&1));
+    let json = serde_json::to_string(&tree).unwrap();
+    assert_eq!(json, "[1,3,3,5]");
+
+    let loaded: SplayTree<i32> = serde_json::from_str("[9,2,7,2]").unwrap();
+    assert_eq!(loaded.len(), 4);
+    assert_eq!(loaded.sorted_vec(), [&2, &2, &7, &9]);
+    assert_eq!(serde_json::from_str::<SplayTree<i32>>(&json).unwrap(), tree);
+}
@@ -0,0 +1,262 @@
+use super::node::Node;
+use std::cmp::Ordering;
+
+/// A splay tree, ordered so that no value in a node's left subtree is greater
+/// than it and no value in its right subtree is less. Its shape is not
+/// bounded, so a run of sorted inserts leaves a path as deep as the tree is
+/// large; every walk over it is iterative.
+pub struct Tree<T: Ord>(pub Option<Box<Node<T>>>);
+
+impl<T: Ord> Tree<T> {
+    pub fn take(&mut self) -> Tree<T> {
+        Tree(self.0.take())
+    }
+
+    /// Moves the node where a search guided by `compare` ends to the root,
+    /// top-down: the nodes passed on the way are split off into a tree of
+    /// smaller and a tree of larger values, and pairs of steps in the same
+    /// direction are rotated first, which roughly halves the depth of every
+    /// node on the path. `compare` gives the ordering of a value relative to
+    /// the target, so a constant `Greater` or `Less` splays the minimum or
+    /// maximum.
+    pub fn splay_by<F: Fn(&T) -> Ordering>(&mut self, compare: F) {
+        let Some(mut root) = self.0.take() else {
+            return;
+        };
+
+        // Nodes left behind, from the top down: `lesser` are still missing
+        // their right child and `greater` their left child
+        let mut lesser: Vec<Box<Node<T>>> = Vec::new();
+        let mut greater: Vec<Box<Node<T>>> = Vec::new();
+
+        loop {
+            match compare(&root.value) {
+                Ordering::Greater => {
+                    let Some(mut left) = root.left.0.take() else {
+                        break;
+                    };
+                    if compare(&left.value) == Ordering::Greater {
+                        root.left = left.right.take();
+                        left.right = Tree(Some(root));
+                        root = left;
+                        let Some(next) = root.left.0.take() else {
+                            break;
+                        };
+                        greater.push(root);
+                        root = next;
+                    } else {
+                        greater.push(root);
+                        root = left;
+                    }
+                }
+                Ordering::Less => {
+                    let Some(mut right) = root.right.0.take() else {
+                        break;
+                    };
+                    if compare(&right.value) == Ordering::Less {
+                        root.right = right.left.take();
+                        right.left = Tree(Some(root));
+                        root = right;
+                        let Some(next) = root.right.0.take() else {
+                            break;
+                        };
+                        lesser.push(root);
+                        root = next;
+                    } else {
+                        lesser.push(root);
+                        root = right;
+                    }
+                }
+                Ordering::Equal => break,
+            }
+        }
+
+        let mut left = root.left.take();
+        for mut node in lesser.into_iter().rev() {
+            node.right = left;
+            left = Tree(Some(node));
+        }
+        let mut right = root.right.take();
+        for mut node in greater.into_iter().rev() {
+            node.left = right;
+            right = Tree(Some(node));
+        }
+
+        root.left = left;
+        root.right = right;
+        self.0 = Some(root);
+    }
+
+    /// Splays `target` and returns whether the root is now equal to it
+    pub fn splay(&mut self, target: &T) -> bool {
+        self.splay_by(|value| value.cmp(target));
+        self.0.as_ref().is_some_and(|node| node.value == *target)
+    }
+
+    /// Inserts `value` at the root, before any equal values, returning
+    /// whether there were any. If there were and `allow_duplicate` is not
+    /// set, nothing is inserted.
+    pub fn insert(&mut self, value: T, allow_duplicate: bool) -> bool {
+        let is_duplicate = self.splay(&value);
+        if is_duplicate && !allow_duplicate {
+            return true;
+        }
+
+        let mut node = Box::new(Node::new(value));
+        if let Some(mut root) = self.0.take() {
+            if root.value < node.value {
+                node.right = root.right.take();
+                node.left = Tree(Some(root));
+            } else {
+                node.left = root.left.take();
+                node.right = Tree(Some(root));
+            }
+        }
+        self.0 = Some(node);
+        is_duplicate
+    }
+
+    /// Joins two trees where no value of `left` is greater than any value of
+    /// `right`, by splaying the maximum of `left` and hanging `right` off it
+    fn join(mut left: Tree<T>, right: Tree<T>) -> Tree<T> {
+        left.splay_by(|_| Ordering::Less);
+        match left.0.as_mut() {
+            Some(node) => node.right = right,
+            None => return right,
+        }
+        left
+    }
+
+    /// Splays `target` and removes it from the root
+    pub fn remove(&mut self, target: &T) -> bool {
+        if !self.splay(target) {
+            return false;
+        }
+
+        let Node { left, right, .. } = *self.0.take().unwrap();
+        *self = Tree::join(left, right);
+        true
+    }
+
+    pub fn extract_min(&mut self) -> Option<T> {
+        self.splay_by(|_| Ordering::Greater);
+        let Node { value, right, .. } = *self.0.take()?;
+        *self = right;
+        Some(value)
+    }
+
+    pub fn extract_max(&mut self) -> Option<T> {
+        self.splay_by(|_| Ordering::Less);
+        let Node { value, left, .. } = *self.0.take()?;
+        *self = left;
+        Some(value)
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        let mut node = self.0.as_ref()?;
+        while let Some(left) = node.left.0.as_ref() {
+            node = left;
+        }
+        Some(&node.value)
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        let mut node = self.0.as_ref()?;
+        while let Some(right) = node.right.0.as_ref() {
+            node = right;
+        }
+        Some(&node.value)
+    }
+
+    /// Smallest value greater than `value`
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        let mut current = self;
+        let mut successor = None;
+
+        while let Some(ref node) = current.0 {
+            if node.value > *value {
+                successor = Some(&node.value);
+                current = &node.left;
+            } else {
+                current = &node.right;
+            }
+        }
+
+        successor
+    }
+
+    /// Largest value less than `value`
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        let mut current = self;
+        let mut predecessor = None;
+
+        while let Some(ref node) = current.0 {
+            if node.value < *value {
+                predecessor = Some(&node.value);
+                current = &node.right;
+            } else {
+                current = &node.left;
+            }
+        }
+
+        predecessor
+    }
+
+    /// Number of edges between the root and a value equal to `target`,
+    /// without splaying it
+    pub fn depth_of(&self, target: &T) -> Option<usize> {
+        let mut current = self;
+        let mut depth = 0;
+
+        while let Some(ref node) = current.0 {
+            match node.value.cmp(target) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Less => current = &node.right,
+                Ordering::Equal => return Some(depth),
+            }
+            depth += 1;
+        }
+
+        None
+    }
+
+    pub fn height(&self) -> usize {
+        let mut height = 0;
+        let mut stack: Vec<(&Node<T>, usize)> = self
+            .0
+            .as_deref()
+            .into_iter()
+            .map(|node| (node, 1))
+            .collect();
+
+        while let Some((node, depth)) = stack.pop() {
+            height = height.max(depth);
+            for child in [&node.left, &node.right] {
+                if let Some(child) = child.0.as_deref() {
+                    stack.push((child, depth + 1));
+                }
+            }
+        }
+
+        height
+    }
+}
+
+impl<T: Ord> Drop for Tree<T> {
+    /// Frees the nodes one at a time, rotating left children up so that no
+    /// node is dropped while it still has a subtree, as a splay tree can be
+    /// too deep to let each `Box` drop its children recursively
+    fn drop(&mut self) {
+        let mut current = self.0.take();
+
+        while let Some(mut node) = current {
+            if let Some(mut left) = node.left.0.take() {
+                node.left = left.right.take();
+                left.right = Tree(Some(node));
+                current = Some(left);
+            } else {
+                current = node.right.0.take();
+            }
+        }
+    }
+}
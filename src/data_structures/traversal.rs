@@ -0,0 +1,332 @@
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    ops::{Bound, RangeBounds},
+    ptr,
+};
+
+/// A node of a binary tree, as seen by the traversal iterators. Implemented by
+/// the node types of every tree in `data_structures` so they can all share
+/// the same traversals.
+pub trait BinaryNode {
+    type Value;
+
+    fn value(&self) -> &Self::Value;
+    fn left(&self) -> Option<&Self>;
+    fn right(&self) -> Option<&Self>;
+}
+
+pub struct InorderTraversal<'a, N: 'a + BinaryNode> {
+    pub stack: Vec<Option<&'a N>>,
+    pub current: Option<&'a N>,
+}
+
+pub struct ReverseOrderTraversal<'a, N: 'a + BinaryNode> {
+    pub stack: Vec<Option<&'a N>>,
+    pub current: Option<&'a N>,
+}
+
+pub struct PreorderTraversal<'a, N: 'a + BinaryNode> {
+    pub stack: Vec<Option<&'a N>>,
+    pub current: Option<&'a N>,
+}
+
+pub struct PostorderTraversal<'a, N: 'a + BinaryNode> {
+    pub stack: Vec<Option<&'a N>>,
+    pub current: Option<&'a N>,
+}
+
+pub struct LevelOrderTraversal<'a, N: 'a + BinaryNode> {
+    pub deque: VecDeque<&'a N>,
+}
+
+/// Inorder traversal of the elements within a range, which can be consumed
+/// from both ends. Only the nodes on the paths to the range's bounds are
+/// visited before the first element is yielded.
+pub struct Range<'a, N: 'a + BinaryNode> {
+    pub front: Vec<&'a N>,
+    pub back: Vec<&'a N>,
+    pub remaining: usize,
+}
+
+impl<'a, N: 'a + BinaryNode> InorderTraversal<'a, N> {
+    pub fn new(root: Option<&'a N>) -> Self {
+        InorderTraversal {
+            stack: Vec::new(),
+            current: root,
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> ReverseOrderTraversal<'a, N> {
+    pub fn new(root: Option<&'a N>) -> Self {
+        ReverseOrderTraversal {
+            stack: Vec::new(),
+            current: root,
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> PreorderTraversal<'a, N> {
+    pub fn new(root: Option<&'a N>) -> Self {
+        PreorderTraversal {
+            stack: vec![root],
+            current: root,
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> PostorderTraversal<'a, N> {
+    pub fn new(root: Option<&'a N>) -> Self {
+        PostorderTraversal {
+            stack: Vec::new(),
+            current: root,
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> LevelOrderTraversal<'a, N> {
+    pub fn new(root: Option<&'a N>) -> Self {
+        let mut deque = VecDeque::new();
+        if let Some(root) = root {
+            deque.push_back(root);
+        }
+        LevelOrderTraversal { deque }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Range<'a, N>
+where
+    N::Value: Ord,
+{
+    /// `len` must be the number of elements of the tree within `range`
+    pub fn new<R: RangeBounds<N::Value>>(root: Option<&'a N>, range: &R, len: usize) -> Self {
+        Range::new_by(root, range, len, Ord::cmp)
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Range<'a, N> {
+    /// Like `new`, for a tree ordered by `compare` instead of `Ord`
+    pub fn new_by<R, F>(root: Option<&'a N>, range: &R, len: usize, compare: F) -> Self
+    where
+        R: RangeBounds<N::Value>,
+        F: Fn(&N::Value, &N::Value) -> Ordering,
+    {
+        let mut front = Vec::new();
+        let mut current = root;
+        while let Some(node) = current {
+            let after_start = match range.start_bound() {
+                Bound::Included(start) => compare(node.value(), start).is_ge(),
+                Bound::Excluded(start) => compare(node.value(), start).is_gt(),
+                Bound::Unbounded => true,
+            };
+            if after_start {
+                front.push(node);
+                current = node.left();
+            } else {
+                current = node.right();
+            }
+        }
+
+        let mut back = Vec::new();
+        let mut current = root;
+        while let Some(node) = current {
+            let before_end = match range.end_bound() {
+                Bound::Included(end) => compare(node.value(), end).is_le(),
+                Bound::Excluded(end) => compare(node.value(), end).is_lt(),
+                Bound::Unbounded => true,
+            };
+            if before_end {
+                back.push(node);
+                current = node.right();
+            } else {
+                current = node.left();
+            }
+        }
+
+        Range {
+            front,
+            back,
+            remaining: len,
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Iterator for InorderTraversal<'a, N> {
+    type Item = &'a N::Value;
+
+    fn next(&mut self) -> Option<&'a N::Value> {
+        loop {
+            if let Some(current) = self.current {
+                self.stack.push(self.current);
+                self.current = current.left();
+            } else {
+                if let Some(node) = self.stack.pop() {
+                    let current = node.unwrap();
+                    let element = current.value();
+                    self.current = current.right();
+                    return Some(element);
+                } else {
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Iterator for ReverseOrderTraversal<'a, N> {
+    type Item = &'a N::Value;
+
+    fn next(&mut self) -> Option<&'a N::Value> {
+        loop {
+            if let Some(current) = self.current {
+                self.stack.push(self.current);
+                self.current = current.right();
+            } else {
+                if let Some(node) = self.stack.pop() {
+                    let current = node.unwrap();
+                    let element = current.value();
+                    self.current = current.left();
+                    return Some(element);
+                } else {
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Iterator for PreorderTraversal<'a, N> {
+    type Item = &'a N::Value;
+
+    fn next(&mut self) -> Option<&'a N::Value> {
+        loop {
+            if let Some(current) = self.current {
+                let element = current.value();
+                self.current = current.left();
+                self.stack.push(self.current);
+                return Some(element);
+            } else {
+                if let Some(node) = self.stack.pop() {
+                    if let Some(current) = node {
+                        self.current = current.right();
+                        if self.current.is_some() {
+                            self.stack.push(self.current);
+                        }
+                    }
+                } else {
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Iterator for PostorderTraversal<'a, N> {
+    type Item = &'a N::Value;
+
+    fn next(&mut self) -> Option<&'a N::Value> {
+        loop {
+            // Go down the left branch and add nodes along with their right
+            // children to the stack
+            while let Some(current) = self.current {
+                if current.right().is_some() {
+                    self.stack.push(current.right());
+                }
+                self.stack.push(self.current);
+                self.current = current.left();
+            }
+
+            if self.stack.is_empty() {
+                return None;
+            }
+
+            if let Some(root) = self.stack.pop().unwrap() {
+                // If the popped item has a right child, and the right child
+                // hasn't been processed yet, make sure the right child is
+                // processed before root
+                if !self.stack.is_empty()
+                    && root.right().is_some()
+                    && ptr::eq(self.stack.last().unwrap().unwrap(), root.right().unwrap())
+                {
+                    self.stack.pop(); // Remove the right child from stack
+                    self.stack.push(Some(root)); // Push the root back to stack
+
+                    // Changes the current node so that the root's right
+                    // child is viewed first
+                    self.current = root.right();
+                } else {
+                    let element = root.value();
+                    self.current = None;
+                    return Some(element);
+                }
+            } else {
+                return None; // Only empty nodes remain
+            }
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Iterator for LevelOrderTraversal<'a, N> {
+    type Item = &'a N::Value;
+
+    fn next(&mut self) -> Option<&'a N::Value> {
+        if let Some(node) = self.deque.pop_front() {
+            if let Some(left) = node.left() {
+                self.deque.push_back(left);
+            }
+
+            if let Some(right) = node.right() {
+                self.deque.push_back(right);
+            }
+
+            Some(node.value())
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> Iterator for Range<'a, N> {
+    type Item = &'a N::Value;
+
+    fn next(&mut self) -> Option<&'a N::Value> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let node = self.front.pop()?;
+        let mut current = node.right();
+        while let Some(next) = current {
+            self.front.push(next);
+            current = next.left();
+        }
+
+        Some(node.value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> DoubleEndedIterator for Range<'a, N> {
+    fn next_back(&mut self) -> Option<&'a N::Value> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let node = self.back.pop()?;
+        let mut current = node.left();
+        while let Some(next) = current {
+            self.back.push(next);
+            current = next.right();
+        }
+
+        Some(node.value())
+    }
+}
+
+impl<'a, N: 'a + BinaryNode> ExactSizeIterator for Range<'a, N> {}
@@ -0,0 +1,65 @@
+use super::node::Node;
+use crate::data_structures::traversal::{
+    LevelOrderTraversal, PostorderTraversal, PreorderTraversal, Range,
+};
+
+/// Inorder iterator over the elements of a `Treap`, which can be consumed
+/// from both ends
+pub struct Iter<'a, T: Ord + 'a> {
+    pub(super) range: Range<'a, Node<T>>,
+}
+
+/// Preorder iterator over the elements of a `Treap`, so every element comes
+/// before the elements of lower priority below it
+pub struct Preorder<'a, T: Ord + 'a>(pub(super) PreorderTraversal<'a, Node<T>>);
+
+/// Postorder iterator over the elements of a `Treap`
+pub struct Postorder<'a, T: Ord + 'a>(pub(super) PostorderTraversal<'a, Node<T>>);
+
+/// Iterator over the elements of a `Treap` level by level, from the root down
+/// and left to right within a level
+pub struct LevelOrder<'a, T: Ord + 'a>(pub(super) LevelOrderTraversal<'a, Node<T>>);
+
+impl<'a, T: Ord + 'a> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.range.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<'a, T: Ord + 'a> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.range.next_back()
+    }
+}
+
+impl<'a, T: Ord + 'a> ExactSizeIterator for Iter<'a, T> {}
+
+impl<'a, T: Ord + 'a> Iterator for Preorder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+}
+
+impl<'a, T: Ord + 'a> Iterator for Postorder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+}
+
+impl<'a, T: Ord + 'a> Iterator for LevelOrder<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.0.next()
+    }
+}
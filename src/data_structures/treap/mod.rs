@@ -0,0 +1,14 @@
+#[allow(clippy::module_inception)]
+mod treap;
+pub use treap::Treap;
+
+mod iter;
+pub use iter::{Iter, LevelOrder, Postorder, Preorder};
+
+mod node;
+#[cfg(feature = "serde")]
+mod serde;
+mod tree;
+
+#[cfg(test)]
+mod tests;
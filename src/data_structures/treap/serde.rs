@@ -0,0 +1,28 @@
+//! `Serialize` and `Deserialize` for `Treap`, enabled by the `serde`
+//! feature. Treaps are written as a sequence of their elements in sorted
+//! order, like a `BinarySearchTree`; priorities are not written.
+
+use super::treap::Treap;
+use serde::{
+    de::{Deserialize, Deserializer},
+    ser::{Serialize, SerializeSeq, Serializer},
+};
+
+impl<T: Ord + Serialize> Serialize for Treap<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for value in self.inorder() {
+            seq.serialize_element(value)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de, T: Ord + Deserialize<'de>> Deserialize<'de> for Treap<T> {
+    /// Inserts the elements of a sequence in any order, keeping duplicates.
+    /// Each gets a new random priority, from a generator seeded by the
+    /// operating system as in `Treap::new`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
@@ -0,0 +1,277 @@
+use super::tree::Tree;
+use crate::data_structures::{
+    binary_search_tree::BinarySearchTree,
+    treap::{Iter, LevelOrder, Postorder, Preorder, Treap},
+};
+use std::iter::Rev;
+
+/// Checks that `tree` is ordered by value, heap-ordered by priority and
+/// that every node's size is right, returning its size
+fn check_invariants(tree: &Tree<i32>) -> usize {
+    let Some(node) = tree.0.as_ref() else {
+        return 0;
+    };
+
+    for child in [&node.left, &node.right] {
+        if let Some(child) = child.0.as_ref() {
+            assert!(child.priority <= node.priority);
+        }
+    }
+    assert!(node.left.max().is_none_or(|max| *max <= node.value));
+    assert!(node.right.min().is_none_or(|min| *min >= node.value));
+
+    let size = 1 + check_invariants(&node.left) + check_invariants(&node.right);
+    assert_eq!(node.size, size);
+    size
+}
+
+/// Height a treap of `size` elements stays under, far above the expected
+/// 3 * log2(n) so that a fixed seed cannot make it flaky
+fn height_bound(size: usize) -> usize {
+    6 * ((size + 1) as f64).log2().ceil() as usize
+}
+
+#[test]
+fn basics() {
+    let mut treap: Treap<i32> = Treap::new();
+
+    // Check empty Treap behaves correctly
+    assert!(treap.is_empty());
+    assert_eq!(treap.size, 0);
+    assert_eq!(treap.height(), 0);
+    assert_eq!(treap.min(), None);
+    assert_eq!(treap.max(), None);
+    assert!(!treap.contains(&5));
+    assert!(!treap.remove(&5));
+
+    assert!(!treap.insert(1));
+    assert!(!treap.insert(0));
+    assert!(!treap.insert(-2));
+    assert!(!treap.insert(5));
+    assert!(treap.insert(5)); // duplicate
+    assert!(!treap.insert(15));
+    assert!(!treap.insert(-5));
+    assert!(!treap.insert(3));
+    assert!(treap.insert(0)); // duplicate
+    assert_eq!(check_invariants(&treap.root), 9);
+
+    assert_eq!(treap.size, 9);
+    assert_eq!(
+        treap.sorted_vec(),
+        vec![&-5, &-2, &0, &0, &1, &3, &5, &5, &15]
+    );
+    assert_eq!(treap.min(), Some(&-5));
+    assert_eq!(treap.max(), Some(&15));
+    assert!(treap.contains(&3));
+    assert!(!treap.contains(&55));
+    assert_eq!(treap.successor(&0), Some(&1));
+    assert_eq!(treap.predecessor(&0), Some(&-2));
+
+    assert!(!treap.remove(&100));
+    assert!(treap.remove(&0));
+    assert!(treap.remove(&5));
+    assert_eq!(treap.sorted_vec(), vec![&-5, &-2, &0, &1, &3, &5, &15]);
+    assert_eq!(check_invariants(&treap.root), 7);
+    assert_eq!(treap.extract_min(), Some(-5));
+    assert_eq!(treap.extract_max(), Some(15));
+    assert!(!treap.insert_without_dup(4));
+    assert!(treap.insert_without_dup(4));
+    assert_eq!(format!("{:?}", treap), "{-2, 0, 1, 3, 4, 5}");
+    assert_eq!(treap.into_sorted_vec(), [-2, 0, 1, 3, 4, 5]);
+
+    let mut treap: Treap<i32> = (0..10).collect();
+    treap.clear();
+    assert!(treap.is_empty());
+    assert_eq!(treap.root(), None);
+}
+
+#[test]
+fn traversals() {
+    // The shape depends on the priorities, so only check what any heap order
+    // guarantees
+    let mut treap: Treap<i32> = Treap::with_seed(7);
+    treap.extend(1..=7);
+    let mut preorder: Vec<&i32> = treap.preorder().collect();
+    assert_eq!(preorder[0], treap.root().unwrap());
+    preorder.sort();
+    assert_eq!(preorder, treap.sorted_vec());
+
+    assert_eq!(
+        treap.inorder().collect::<Vec<&i32>>(),
+        vec![&1, &2, &3, &4, &5, &6, &7]
+    );
+    assert_eq!(
+        treap.reverse_order().collect::<Vec<&i32>>(),
+        vec![&7, &6, &5, &4, &3, &2, &1]
+    );
+    assert_eq!(treap.postorder().last(), treap.root());
+    assert_eq!(treap.level_order().next(), treap.root());
+    assert_eq!(treap.level_order().count(), 7);
+}
+
+#[test]
+fn iter() {
+    let mut treap: Treap<i32> = Treap::with_seed(3);
+    treap.extend(vec![7, 1, 0, 4, 2, 5, 3, 6, 8]);
+    let mut iter = treap.iter();
+    assert_eq!(iter.len(), 9);
+    assert_eq!(iter.next(), Some(&0));
+    assert_eq!(iter.next_back(), Some(&8));
+    assert_eq!(iter.len(), 7);
+    assert_eq!(
+        iter.rev().collect::<Vec<&i32>>(),
+        vec![&7, &6, &5, &4, &3, &2, &1]
+    );
+
+    let mut elements = Vec::new();
+    for element in &treap {
+        elements.push(*element);
+    }
+    assert_eq!(elements, (0..=8).collect::<Vec<i32>>());
+}
+
+#[test]
+fn traversal_types() {
+    // Every traversal has a type that can be named outside the module, of the
+    // same kind as the matching `BinarySearchTree` one
+    struct Traversals<'a> {
+        inorder: Iter<'a, i32>,
+        reverse_order: Rev<Iter<'a, i32>>,
+        preorder: Preorder<'a, i32>,
+        postorder: Postorder<'a, i32>,
+        level_order: LevelOrder<'a, i32>,
+    }
+
+    let treap: Treap<i32> = vec![2, 1, 3].into_iter().collect();
+    let tree: BinarySearchTree<i32> = vec![2, 1, 3].into_iter().collect();
+    let traversals = Traversals {
+        inorder: treap.inorder(),
+        reverse_order: treap.reverse_order(),
+        preorder: treap.preorder(),
+        postorder: treap.postorder(),
+        level_order: treap.level_order(),
+    };
+    assert!(traversals.inorder.eq(tree.inorder()));
+    assert!(traversals.reverse_order.eq(tree.reverse_order()));
+    assert_eq!(traversals.preorder.count(), 3);
+    assert_eq!(traversals.postorder.count(), 3);
+    assert_eq!(traversals.level_order.count(), 3);
+}
+
+#[test]
+fn sorted_inserts_stay_shallow() {
+    let mut treap: Treap<i32> = Treap::with_seed(1);
+    for i in 0..10_000 {
+        treap.insert(i);
+    }
+    assert_eq!(check_invariants(&treap.root), 10_000);
+    assert!(treap.height() <= height_bound(treap.len()));
+
+    for i in (0..10_000).step_by(2) {
+        assert!(treap.remove(&i));
+    }
+    assert_eq!(check_invariants(&treap.root), 5_000);
+    assert!(treap.height() <= height_bound(treap.len()));
+    assert_eq!(treap.min(), Some(&1));
+    assert_eq!(treap.max(), Some(&9_999));
+}
+
+#[test]
+fn same_seed_same_shape() {
+    let mut a: Treap<i32> = Treap::with_seed(42);
+    let mut b: Treap<i32> = Treap::with_seed(42);
+    a.extend(0..1_000);
+    b.extend(0..1_000);
+    assert!(a.preorder().eq(b.preorder()));
+}
+
+#[test]
+fn matches_binary_search_tree() {
+    let mut treap: Treap<i32> = Treap::with_seed(3);
+    let mut unbalanced: BinarySearchTree<i32> = BinarySearchTree::new_unbalanced();
+
+    for i in 0..2_000 {
+        let value = (i * 37) % 311;
+        assert_eq!(treap.insert(value), unbalanced.insert(value));
+        assert_eq!(
+            treap.insert_without_dup(value + 1),
+            unbalanced.insert_without_dup(value + 1)
+        );
+    }
+    for i in 0..1_500 {
+        let value = (i * 53) % 317;
+        assert_eq!(treap.remove(&value), unbalanced.remove(&value));
+        assert_eq!(treap.successor(&value), unbalanced.successor(&value));
+        assert_eq!(treap.predecessor(&value), unbalanced.predecessor(&value));
+    }
+    for _ in 0..100 {
+        assert_eq!(treap.extract_min(), unbalanced.extract_min());
+        assert_eq!(treap.extract_max(), unbalanced.extract_max());
+    }
+
+    assert_eq!(treap.len(), unbalanced.len());
+    assert_eq!(treap.sorted_vec(), unbalanced.sorted_vec());
+    assert_eq!(check_invariants(&treap.root), treap.len());
+}
+
+#[test]
+fn split_off_and_append() {
+    let mut low: Treap<i32> = Treap::with_seed(5);
+    low.extend((0..1_000).chain(0..1_000));
+
+    let mut high = low.split_off(&600);
+    assert_eq!(low.len(), 1_200);
+    assert_eq!(high.len(), 800);
+    assert_eq!(low.max(), Some(&599));
+    assert_eq!(high.min(), Some(&600));
+    assert_eq!(check_invariants(&low.root), 1_200);
+    assert_eq!(check_invariants(&high.root), 800);
+
+    // Splitting outside the range moves everything or nothing
+    assert!(low.split_off(&1_000).is_empty());
+    let mut all = high.split_off(&0);
+    assert!(high.is_empty());
+    assert_eq!(all.len(), 800);
+
+    low.append(&mut all);
+    assert!(all.is_empty());
+    assert_eq!(check_invariants(&low.root), 2_000);
+    assert!(low.inorder().copied().eq((0..1_000).flat_map(|i| [i, i])));
+
+    // Interleaved values are unioned rather than merged
+    let mut evens: Treap<i32> = Treap::with_seed(8);
+    let mut odds: Treap<i32> = Treap::with_seed(9);
+    evens.extend((0..2_000).step_by(2));
+    odds.extend((1..2_000).step_by(2));
+    odds.insert(1_000);
+    evens.append(&mut odds);
+    assert_eq!(check_invariants(&evens.root), 2_001);
+    assert_eq!(evens.len(), 2_001);
+    assert!(evens.height() <= height_bound(evens.len()));
+    assert_eq!(evens.successor(&999), Some(&1_000));
+    assert!(evens.remove(&1_000));
+    assert!(evens.remove(&1_000));
+    assert!(!evens.contains(&1_000));
+    assert!(
+        evens
+            .inorder()
+            .copied()
+            .eq((0..2_000).filter(|i| *i != 1_000))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+    let treap: Treap<i32> = vec![5, 1, 3, 3].into_iter().collect();
+    let json = serde_json::to_string(&treap).unwrap();
+    assert_eq!(json, "[1,3,3,5]");
+
+    let mut loaded: Treap<i32> = serde_json::from_str("[9,2,7,2]").unwrap();
+    assert_eq!(check_invariants(&loaded.root), 4);
+    assert_eq!(loaded.sorted_vec(), [&2, &2, &7, &9]);
+    loaded.extend(0..1_000);
+    assert_eq!(check_invariants(&loaded.root), 1_004);
+    assert!(loaded.height() <= height_bound(loaded.len()));
+    assert_eq!(serde_json::from_str::<Treap<i32>>(&json).unwrap(), treap);
+}
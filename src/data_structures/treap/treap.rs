@@ -0,0 +1,296 @@
+use super::{
+    iter::{Iter, LevelOrder, Postorder, Preorder},
+    tree::Tree,
+};
+use crate::data_structures::traversal::{
+    LevelOrderTraversal, PostorderTraversal, PreorderTraversal, Range,
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::{
+    cmp::PartialEq,
+    fmt,
+    iter::{Extend, FromIterator, Rev},
+};
+
+/// An ordered set backed by a treap. Every element gets a random priority
+/// and the tree is kept heap-ordered by them, which makes its expected height
+/// O(log n) whatever order the elements arrive in, without any rebalancing
+/// rules. Every change is made by splitting the tree at a value and merging
+/// the pieces back, so whole sets can be split apart and joined cheaply.
+pub struct Treap<T: Ord> {
+    pub(super) root: Tree<T>,
+    pub size: usize,
+    rng: StdRng,
+}
+
+impl<T: Ord + fmt::Debug> fmt::Debug for Treap<T> {
+    /// Lists the elements in sorted order, leaving out their priorities and
+    /// the state of the random number generator
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.inorder()).finish()
+    }
+}
+
+impl<T: Ord> PartialEq for Treap<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.inorder().eq(other.inorder())
+    }
+}
+
+impl<T: Ord + fmt::Debug> fmt::Display for Treap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.sorted_vec())
+    }
+}
+
+impl<T: Ord> Extend<T> for Treap<T> {
+    /// Extends Treap elements from iterators
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::treap::Treap;
+    /// use std::iter::Extend;
+    ///
+    /// let mut treap: Treap<i32> = Treap::new();
+    /// treap.extend(vec![7, 1, 0, 4, 5, 3].into_iter());
+    /// assert_eq!(treap.sorted_vec(), [&0, &1, &3, &4, &5, &7]);
+    /// ```
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |element| {
+            self.insert(element);
+        });
+    }
+}
+
+impl<T: Ord> FromIterator<T> for Treap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut treap = Treap::new();
+        treap.extend(iter);
+        treap
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a Treap<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Default for Treap<T> {
+    fn default() -> Self {
+        Treap::new()
+    }
+}
+
+impl<T: Ord> Treap<T> {
+    /// Creates an empty treap whose priorities are drawn from a generator
+    /// seeded by the operating system
+    pub fn new() -> Self {
+        Treap::from_rng(StdRng::from_rng(&mut rand::rng()))
+    }
+
+    /// Creates an empty treap whose priorities, and so its shape after a
+    /// given sequence of changes, are the same on every run
+    pub fn with_seed(seed: u64) -> Self {
+        Treap::from_rng(StdRng::seed_from_u64(seed))
+    }
+
+    fn from_rng(rng: StdRng) -> Self {
+        Treap {
+            root: Tree(None),
+            size: 0,
+            rng,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Removes every element, keeping the priority generator
+    pub fn clear(&mut self) {
+        self.root = Tree(None);
+        self.size = 0;
+    }
+
+    pub fn root(&self) -> Option<&T> {
+        self.root.0.as_ref().map(|node| &node.value)
+    }
+
+    /// Number of nodes on the longest path from the root to a leaf, about
+    /// 3 * log2(n) in expectation
+    pub fn height(&self) -> usize {
+        self.root.height()
+    }
+
+    pub fn insert(&mut self, value: T) -> bool {
+        self.size += 1;
+        let priority = self.rng.random();
+        self.root.insert(value, priority, true)
+    }
+
+    pub fn insert_without_dup(&mut self, value: T) -> bool {
+        let priority = self.rng.random();
+        let res = self.root.insert(value, priority, false);
+        if !res {
+            self.size += 1;
+        }
+        res
+    }
+
+    pub fn contains(&self, target: &T) -> bool {
+        self.root.contains(target)
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.root.min()
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        self.root.max()
+    }
+
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        self.root.successor(value)
+    }
+
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        self.root.predecessor(value)
+    }
+
+    pub fn extract_min(&mut self) -> Option<T> {
+        let res = self.root.extract_min();
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn extract_max(&mut self) -> Option<T> {
+        let res = self.root.extract_max();
+        if res.is_some() {
+            self.size -= 1;
+        }
+        res
+    }
+
+    pub fn remove(&mut self, target: &T) -> bool {
+        let res = self.root.remove(target);
+        if res {
+            self.size -= 1;
+        }
+        res
+    }
+
+    /// Moves the elements greater than or equal to `key` into a new treap,
+    /// in expected O(log n) time
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::treap::Treap;
+    ///
+    /// let mut low: Treap<i32> = (0..10).collect();
+    /// let high = low.split_off(&6);
+    /// assert_eq!(low.sorted_vec(), [&0, &1, &2, &3, &4, &5]);
+    /// assert_eq!(high.sorted_vec(), [&6, &7, &8, &9]);
+    /// ```
+    pub fn split_off(&mut self, key: &T) -> Treap<T> {
+        let (less, rest) = self.root.take().split(key, false);
+        self.root = less;
+        self.size = self.root.size();
+
+        let mut other = Treap::from_rng(StdRng::from_rng(&mut self.rng));
+        other.size = rest.size();
+        other.root = rest;
+        other
+    }
+
+    /// Moves every element of `other` into this treap. When all of them are
+    /// greater than those already here, as after `split_off`, this is a
+    /// merge taking expected O(log n) time; otherwise the two are unioned
+    /// around their roots, which costs less the fewer runs they interleave in.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::treap::Treap;
+    ///
+    /// let mut evens: Treap<i32> = (0..10).step_by(2).collect();
+    /// let mut odds: Treap<i32> = (1..10).step_by(2).collect();
+    /// evens.append(&mut odds);
+    /// assert_eq!(evens.len(), 10);
+    /// assert!(odds.is_empty());
+    /// assert!(evens.inorder().copied().eq(0..10));
+    /// ```
+    pub fn append(&mut self, other: &mut Treap<T>) {
+        let other_root = other.root.take();
+        self.size += other.size;
+        other.size = 0;
+
+        let joinable = match (self.root.max(), other_root.min()) {
+            (Some(max), Some(min)) => max <= min,
+            _ => true,
+        };
+        self.root = if joinable {
+            Tree::merge(self.root.take(), other_root)
+        } else {
+            Tree::union(self.root.take(), other_root)
+        };
+    }
+
+    pub fn sorted_vec(&self) -> Vec<&T> {
+        self.inorder().collect()
+    }
+
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut elements = Vec::with_capacity(self.size);
+        while let Some(element) = self.root.extract_min() {
+            elements.push(element);
+        }
+        elements
+    }
+
+    /// Returns an iterator over the elements in sorted order, which can also
+    /// be consumed from the back.
+    /// # Example:
+    /// ```
+    /// use data_structures_and_algorithms::data_structures::treap::Treap;
+    ///
+    /// let treap: Treap<i32> = vec![7, 1, 0, 4, 5, 3].into_iter().collect();
+    /// let mut iter = treap.iter();
+    /// assert_eq!(iter.len(), 6);
+    /// assert_eq!(iter.next(), Some(&0));
+    /// assert_eq!(iter.next_back(), Some(&7));
+    /// assert_eq!(iter.collect::<Vec<&i32>>(), [&1, &3, &4, &5]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            range: Range::new(self.root.0.as_deref(), &.., self.size),
+        }
+    }
+
+    /// The same iterator as `iter`
+    pub fn inorder(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+
+    /// `iter` consumed from the back, largest element first
+    pub fn reverse_order(&self) -> Rev<Iter<'_, T>> {
+        self.iter().rev()
+    }
+
+    pub fn preorder(&self) -> Preorder<'_, T> {
+        Preorder(PreorderTraversal::new(self.root.0.as_deref()))
+    }
+
+    pub fn postorder(&self) -> Postorder<'_, T> {
+        Postorder(PostorderTraversal::new(self.root.0.as_deref()))
+    }
+
+    pub fn level_order(&self) -> LevelOrder<'_, T> {
+        LevelOrder(LevelOrderTraversal::new(self.root.0.as_deref()))
+    }
+}
@@ -0,0 +1,226 @@
+use super::node::Node;
+use std::cmp::{Ordering, max};
+
+/// A treap: ordered by value like a search tree, and by priority like a
+/// max-heap, so no node has a higher priority than its parent. Equal values
+/// may sit on either side of each other. With random priorities the shape is
+/// that of a tree built by inserting the values in a random order, whose
+/// expected height is O(log n), so recursing along a path is safe.
+pub struct Tree<T: Ord>(pub Option<Box<Node<T>>>);
+
+impl<T: Ord> Tree<T> {
+    pub fn take(&mut self) -> Tree<T> {
+        Tree(self.0.take())
+    }
+
+    /// Number of nodes in this subtree
+    pub fn size(&self) -> usize {
+        self.0.as_ref().map_or(0, |node| node.size)
+    }
+
+    pub fn height(&self) -> usize {
+        self.0
+            .as_ref()
+            .map_or(0, |node| 1 + max(node.left.height(), node.right.height()))
+    }
+
+    fn update(&mut self) {
+        if let Some(node) = self.0.as_mut() {
+            node.size = 1 + node.left.size() + node.right.size();
+        }
+    }
+
+    /// Splits the tree into the values less than `key` and the rest, or into
+    /// the values less than or equal to `key` and the rest if `inclusive` is
+    /// set. Only the nodes on one path are relinked.
+    pub fn split(self, key: &T, inclusive: bool) -> (Tree<T>, Tree<T>) {
+        let Some(mut node) = self.0 else {
+            return (Tree(None), Tree(None));
+        };
+
+        let goes_left = match node.value.cmp(key) {
+            Ordering::Less => true,
+            Ordering::Equal => inclusive,
+            Ordering::Greater => false,
+        };
+
+        if goes_left {
+            let (less, rest) = node.right.take().split(key, inclusive);
+            node.right = less;
+            let mut left = Tree(Some(node));
+            left.update();
+            (left, rest)
+        } else {
+            let (less, rest) = node.left.take().split(key, inclusive);
+            node.left = rest;
+            let mut right = Tree(Some(node));
+            right.update();
+            (less, right)
+        }
+    }
+
+    /// Joins two treaps, where no value of `left` is greater than any value
+    /// of `right`, walking down their facing spines by priority
+    pub fn merge(left: Tree<T>, right: Tree<T>) -> Tree<T> {
+        let mut merged = match (left.0, right.0) {
+            (None, right) => return Tree(right),
+            (left, None) => return Tree(left),
+            (Some(mut left), Some(right)) if left.priority >= right.priority => {
+                left.right = Tree::merge(left.right.take(), Tree(Some(right)));
+                Tree(Some(left))
+            }
+            (Some(left), Some(mut right)) => {
+                right.left = Tree::merge(Tree(Some(left)), right.left.take());
+                Tree(Some(right))
+            }
+        };
+        merged.update();
+        merged
+    }
+
+    /// Combines two treaps whose values may interleave. The root with the
+    /// higher priority stays on top and the other treap is split around it,
+    /// so runs of values from one treap are moved as whole subtrees.
+    pub fn union(a: Tree<T>, b: Tree<T>) -> Tree<T> {
+        let (mut top, other) = match (a.0, b.0) {
+            (None, b) => return Tree(b),
+            (a, None) => return Tree(a),
+            (Some(a), Some(b)) if a.priority >= b.priority => (a, Tree(Some(b))),
+            (Some(a), Some(b)) => (b, Tree(Some(a))),
+        };
+
+        let (less, rest) = other.split(&top.value, false);
+        top.left = Tree::union(top.left.take(), less);
+        top.right = Tree::union(top.right.take(), rest);
+        let mut tree = Tree(Some(top));
+        tree.update();
+        tree
+    }
+
+    /// Inserts `value` after any equal values, returning whether there were
+    /// any. If there were and `allow_duplicate` is not set, nothing is
+    /// inserted.
+    pub fn insert(&mut self, value: T, priority: u64, allow_duplicate: bool) -> bool {
+        let is_duplicate = self.contains(&value);
+        if is_duplicate && !allow_duplicate {
+            return true;
+        }
+
+        let (less, rest) = self.take().split(&value, true);
+        let node = Tree(Some(Box::new(Node::new(value, priority))));
+        *self = Tree::merge(Tree::merge(less, node), rest);
+        is_duplicate
+    }
+
+    pub fn contains(&self, target: &T) -> bool {
+        let mut current = self;
+
+        while let Some(ref node) = current.0 {
+            match node.value.cmp(target) {
+                Ordering::Greater => current = &node.left,
+                Ordering::Less => current = &node.right,
+                Ordering::Equal => return true,
+            }
+        }
+
+        false
+    }
+
+    /// Removes a value equal to `target` by merging its children in its place
+    pub fn remove(&mut self, target: &T) -> bool {
+        let Some(node) = self.0.as_mut() else {
+            return false;
+        };
+
+        let removed = match node.value.cmp(target) {
+            Ordering::Greater => node.left.remove(target),
+            Ordering::Less => node.right.remove(target),
+            Ordering::Equal => {
+                let Node { left, right, .. } = *self.0.take().unwrap();
+                *self = Tree::merge(left, right);
+                return true;
+            }
+        };
+
+        if removed {
+            self.update();
+        }
+        removed
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        let mut node = self.0.as_ref()?;
+        while let Some(left) = node.left.0.as_ref() {
+            node = left;
+        }
+        Some(&node.value)
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        let mut node = self.0.as_ref()?;
+        while let Some(right) = node.right.0.as_ref() {
+            node = right;
+        }
+        Some(&node.value)
+    }
+
+    pub fn extract_min(&mut self) -> Option<T> {
+        let node = self.0.as_mut()?;
+        if node.left.0.is_some() {
+            let min = node.left.extract_min();
+            self.update();
+            return min;
+        }
+
+        let Node { value, right, .. } = *self.0.take().unwrap();
+        *self = right;
+        Some(value)
+    }
+
+    pub fn extract_max(&mut self) -> Option<T> {
+        let node = self.0.as_mut()?;
+        if node.right.0.is_some() {
+            let max = node.right.extract_max();
+            self.update();
+            return max;
+        }
+
+        let Node { value, left, .. } = *self.0.take().unwrap();
+        *self = left;
+        Some(value)
+    }
+
+    /// Smallest value greater than `value`
+    pub fn successor(&self, value: &T) -> Option<&T> {
+        let mut current = self;
+        let mut successor = None;
+
+        while let Some(ref node) = current.0 {
+            if node.value > *value {
+                successor = Some(&node.value);
+                current = &node.left;
+            } else {
+                current = &node.right;
+            }
+        }
+
+        successor
+    }
+
+    /// Largest value less than `value`
+    pub fn predecessor(&self, value: &T) -> Option<&T> {
+        let mut current = self;
+        let mut predecessor = None;
+
+        while let Some(ref node) = current.0 {
+            if node.value < *value {
+                predecessor = Some(&node.value);
+                current = &node.right;
+            } else {
+                current = &node.left;
+            }
+        }
+
+        predecessor
+    }
+}